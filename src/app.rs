@@ -1,55 +1,163 @@
 use std::{
     error::Error,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
     time::{Duration, Instant},
 };
 
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
-    layout::{Alignment, Constraint, Direction, Layout},
+    crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
+    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     prelude::Backend,
-    style::{Color, Style},
+    style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, BorderType, Borders, Paragraph},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Clear, Gauge, Paragraph, Wrap},
     Frame, Terminal,
 };
 
 use crate::{
-    enums::{pomodoros::Pomodoros, screens::Screens},
-    sound::play_timer_sound,
-    ui::centered_rect,
+    calendar::{self, logical_day, Clock},
+    config::{self, Config},
+    enums::{
+        actions::Actions, anchors::Anchors, background_modes::BackgroundModes,
+        control_commands::ControlCommands, interruptions::Interruptions, layouts::Layouts,
+        pomodoros::Pomodoros, screens::Screens, sizes::Sizes, status_formats::StatusFormats,
+        themes::Themes,
+    },
+    history::{self, Interruption, Record},
+    ipc::control::Request,
+    keymap, notify,
+    sound::{self, Player},
+    state::{self, SavedState},
+    stats::{self, DailyTotals, DayTotals},
+    status::{self, Status},
+    tasks::{self, Task},
+    tui::{self, EventSource, WindowTitle},
+    ui::{self, anchored_rect, centered_rect, Button},
 };
+use pomodoro_tui::PomodoroTimer;
+
+// Wall-clock gaps between ticks longer than this are treated as the machine
+// having been asleep
+const SLEEP_THRESHOLD_SECONDS: i64 = 60;
+const BANNER_DURATION: Duration = Duration::from_secs(5);
+// How often the session in progress is saved, besides on quitting
+const STATE_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+// Keys that can't be changed; the help lists the `[keys]` actions before them
+const KEY_BINDINGS: [(&str, &str); 4] = [
+    ("Enter", "Switch to the next phase type, paused"),
+    ("Backspace", "Restart the current phase"),
+    ("Esc", "Go back / stop the timer"),
+    ("Arrows", "Move around, whatever the movement keys are"),
+];
+// Rows of the settings screen: label, config key, unit and lowest value
+const SETTINGS: [(&str, &str, &str, usize); 4] = [
+    ("Pomodoro", "pomodoro_minutes", "min", 1),
+    ("Short break", "short_break_minutes", "min", 1),
+    ("Long break", "long_break_minutes", "min", 1),
+    ("Pomodoros per long break", "long_break_interval", "", 1),
+];
+// The theme picker comes after the numbers on the settings screen
+const THEME_ROW: usize = SETTINGS.len();
+// How long to wait for input before updating the timer; poll blocks, so
+// an idle app sleeps for most of it
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// Shortest a phase can be made with `-`
+const MIN_PHASE_SECONDS: usize = 60;
+// Below this the screens give way to just the phase and the time left
+const COMPACT_WIDTH: u16 = 20;
+const COMPACT_HEIGHT: u16 = 10;
+// How many times over the big clock may be enlarged on a roomy terminal
+const MAX_CLOCK_SCALE: usize = 3;
+
+// Narrower than this the split layout stacks its timers instead
+const SPLIT_MIN_WIDTH: u16 = 60;
 
 pub struct App {
     is_running: bool,
-    is_pomodoro_running: bool,
     current_screen: Screens,
-    current_type: Pomodoros,
-    pomodoro_time: usize,
-    short_break_time: usize,
-    long_break_time: usize,
-    pomdoros: usize,
-    short_breaks: usize,
-    long_breaks: usize,
-    short_breaks_before_long: usize,
-    elapsed_seconds: usize,
+    screen_stack: Vec<Screens>,
+    paused_in_background: bool,
+    paused_while_unfocused: bool,
+    timer: PomodoroTimer,
+    show_remaining: bool,
+    paused_since: Option<Instant>,
+    config: Config,
+    history: Vec<Record>,
+    history_path: Option<PathBuf>,
+    daily_totals: DailyTotals,
+    stats_path: Option<PathBuf>,
+    state_path: Option<PathBuf>,
+    status_path: Option<PathBuf>,
+    // Commands from scripts and other programs
+    requests: Option<Receiver<Request>>,
+    // What the status file last said, so it's only rewritten on a change
+    written_status: Option<Status>,
+    // Told about each status written, like the file
+    status_watchers: Vec<Sender<Status>>,
+    state_saved_at: Instant,
+    last_tick: Instant,
+    last_tick_at: i64,
+    banner: Option<(String, Instant)>,
+    player: Option<Player>,
+    // Values being edited on the settings screen and the selected row
+    settings_draft: [usize; SETTINGS.len()],
+    settings_index: usize,
+    // The theme before the settings screen started previewing others
+    settings_theme: Themes,
+    // What the pomodoros are being spent on, and the label being typed
+    task: Option<String>,
+    task_input: String,
+    // The task list, where it's saved and the selected row
+    tasks: Vec<Task>,
+    tasks_path: Option<PathBuf>,
+    tasks_index: usize,
+    // Lines the help is scrolled down by
+    help_scroll: u16,
+    // Interruptions logged during the current pomodoro, where they're
+    // written and the one being typed up
+    interruptions: usize,
+    interruptions_path: Option<PathBuf>,
+    interruption_kind: Interruptions,
+    interruption_note: String,
+    // Where the clickable buttons were last drawn and the key each stands for
+    buttons: Vec<(Rect, KeyCode)>,
+    // The last place the mouse was seen, for highlighting buttons
+    mouse_position: Option<Position>,
+    // The window title last sent to the terminal, so it's only sent on change
+    window_title: Option<String>,
+    // Running without a screen, so nothing can be asked of the user
+    headless: bool,
+    // A repeating alert is playing and waits for a key press
+    alert_active: bool,
+    // Phases finished this session, oldest first, for the timeline strip
+    session_phases: Vec<Pomodoros>,
+    // The phase that just ended, while the next one waits to be started
+    awaiting_start: Option<Pomodoros>,
+    // Seconds the machine slept through a running phase, until the
+    // suspend prompt says what to do with them
+    slept: usize,
+    // Elapsed second the focus tick was last played for
+    ticked_second: usize,
+}
+
+// What a tick did, for `on_tick` to act on
+#[derive(Debug, PartialEq)]
+struct Tick {
+    // The last phase that ended, if any did
+    ended: Option<Pomodoros>,
 }
 
+// The app proper is always built with `from_config`
+#[cfg(test)]
 impl Default for App {
     fn default() -> Self {
-        App {
-            is_running: true,
-            is_pomodoro_running: false,
-            current_screen: Screens::Main,
-            current_type: Pomodoros::Pomodoro,
-            pomodoro_time: 20 * 60,
-            short_break_time: 5 * 60,
-            long_break_time: 15 * 60,
-            pomdoros: 0,
-            short_breaks: 0,
-            long_breaks: 0,
-            short_breaks_before_long: 2,
-            elapsed_seconds: 0,
-        }
+        App::new(20 * 60, 5 * 60, 15 * 60, 3)
     }
 }
 
@@ -58,112 +166,769 @@ impl App {
         pomodoro_time: usize,
         short_break_time: usize,
         long_break_time: usize,
-        short_breaks_before_long: usize,
+        long_break_interval: usize,
     ) -> Self {
         App {
             is_running: true,
-            is_pomodoro_running: false,
             current_screen: Screens::Main,
-            current_type: Pomodoros::Pomodoro,
-            pomodoro_time,
-            short_break_time,
-            long_break_time,
-            pomdoros: 0,
-            short_breaks: 0,
-            long_breaks: 0,
-            short_breaks_before_long,
-            elapsed_seconds: 0,
+            screen_stack: Vec::new(),
+            paused_in_background: false,
+            paused_while_unfocused: false,
+            timer: PomodoroTimer::new(
+                Duration::from_secs(pomodoro_time as u64),
+                Duration::from_secs(short_break_time as u64),
+                Duration::from_secs(long_break_time as u64),
+                long_break_interval,
+            ),
+            show_remaining: true,
+            paused_since: Some(Instant::now()),
+            config: Config::default(),
+            history: Vec::new(),
+            history_path: None,
+            daily_totals: DailyTotals::new(),
+            stats_path: None,
+            state_path: None,
+            status_path: None,
+            requests: None,
+            written_status: None,
+            status_watchers: Vec::new(),
+            state_saved_at: Instant::now(),
+            last_tick: Instant::now(),
+            last_tick_at: calendar::now(),
+            banner: None,
+            player: None,
+            settings_draft: [0; SETTINGS.len()],
+            settings_index: 0,
+            settings_theme: Themes::Dark,
+            ticked_second: 0,
+            alert_active: false,
+            buttons: Vec::new(),
+            mouse_position: None,
+            window_title: None,
+            headless: false,
+            task: None,
+            task_input: String::new(),
+            tasks: Vec::new(),
+            tasks_path: None,
+            tasks_index: 0,
+            help_scroll: 0,
+            interruptions: 0,
+            interruptions_path: None,
+            interruption_kind: Interruptions::External,
+            interruption_note: String::new(),
+            session_phases: Vec::new(),
+            awaiting_start: None,
+            slept: 0,
+        }
+    }
+
+    pub fn from_config(
+        config: Config,
+        history: Vec<Record>,
+        daily_totals: DailyTotals,
+        player: Option<Player>,
+    ) -> Self {
+        // The config and the flags turn down lengths this can't hold, but a
+        // phase that long is as good as endless anyway
+        let seconds = |minutes: usize| minutes.saturating_mul(60);
+        let mut app = App::new(
+            seconds(config.pomodoro_minutes),
+            seconds(config.short_break_minutes),
+            seconds(config.long_break_minutes),
+            config.long_break_interval,
+        );
+        if let Some(player) = &player {
+            player.set_volume(config.volume);
+        }
+        let cooldown = Duration::from_secs(seconds(config.cooldown_minutes) as u64);
+        app.timer.set_duration(Pomodoros::Cooldown, cooldown);
+        app.timer.set_target(config.target_pomodoros);
+        app.timer.set_auto_advance(config.auto_advance);
+        let tasks_path = tasks::tasks_path();
+        App {
+            config,
+            history,
+            history_path: history::history_path(),
+            daily_totals,
+            stats_path: stats::stats_path(),
+            state_path: state::state_path(),
+            status_path: status::status_path(),
+            tasks: tasks_path.as_deref().map_or_else(Vec::new, tasks::load),
+            tasks_path,
+            interruptions_path: history::interruptions_path(),
+            player,
+            ..app
         }
     }
 
-    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), Box<dyn Error>> {
-        let tick_rate = Duration::from_millis(1000);
-        let mut last_tick = Instant::now();
+    /// The same status as the status file, sent whenever that is written.
+    pub fn watch_status(&mut self) -> Receiver<Status> {
+        let (sender, receiver) = mpsc::channel();
+        self.status_watchers.push(sender);
+        receiver
+    }
+
+    /// Carries out the commands sent on `requests` from now on.
+    pub fn listen(&mut self, requests: Receiver<Request>) {
+        self.requests = Some(requests);
+    }
+
+    /// Picks up a session saved by an earlier run, paused.
+    pub fn restore(&mut self, saved: SavedState) {
+        self.timer.set_progress(saved.progress);
+        self.task = saved.task;
+        self.show_banner("Picked up the last session — R starts over".to_string());
+    }
+
+    pub fn run<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        events: &mut (impl EventSource + WindowTitle),
+        clock: &impl Clock,
+    ) -> Result<(), Box<dyn Error>> {
         while self.is_running {
             terminal.draw(|f| self.draw_ui(f))?;
-            let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-            if event::poll(timeout)? {
-                self.check_keys()?;
+            if self.config.window_title {
+                let title = self.window_title();
+                if self.window_title.as_ref() != Some(&title) {
+                    events.set_title(&title)?;
+                    self.window_title = Some(title);
+                }
             }
-            if last_tick.elapsed() >= tick_rate {
-                self.on_tick();
-                last_tick = Instant::now();
+            if let Some(event) = events.next_event(POLL_INTERVAL)? {
+                // Catch the timer up first, so a pause or resume takes
+                // effect at the key press rather than at the last tick
+                self.on_tick(clock.instant(), clock.timestamp());
+                match event {
+                    Event::Key(key) => self.check_keys(key),
+                    Event::Mouse(mouse) => self.mouse_event(mouse),
+                    Event::FocusLost => self.focus_changed(false),
+                    Event::FocusGained => self.focus_changed(true),
+                    // Redraw everything so a shrunk window keeps no stale cells
+                    Event::Resize(..) => terminal.clear()?,
+                    _ => {}
+                }
             }
+            self.step(clock);
         }
+        self.shut_down();
         Ok(())
     }
 
-    fn check_keys(&mut self) -> Result<(), Box<dyn Error>> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Release {
-                return Ok(());
-            }
-            match key.code {
-                KeyCode::Char('q') => {
-                    match self.current_screen {
-                        Screens::Quit => self.is_running = false,
-                        Screens::Pomodoro => self.is_pomodoro_running = false,
-                        _ => {}
+    /// Runs the timer without a terminal, driven only by the requests given
+    /// to `listen`, until one says to quit or the process is interrupted.
+    pub fn run_headless(&mut self, clock: &impl Clock) {
+        self.headless = true;
+        while self.is_running {
+            // Sleeps until a command comes in or it's time to tick
+            let request = match &self.requests {
+                Some(requests) => match requests.recv_timeout(POLL_INTERVAL) {
+                    Ok(request) => Some(request),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        self.requests = None;
+                        None
                     }
-                    self.current_screen = Screens::Quit;
+                },
+                None => {
+                    thread::sleep(POLL_INTERVAL);
+                    None
                 }
+            };
+            if let Some(request) = request {
+                self.on_tick(clock.instant(), clock.timestamp());
+                self.answer(request, clock.timestamp());
+            }
+            self.step(clock);
+        }
+        self.shut_down();
+    }
 
-                KeyCode::Char(' ') => match self.current_screen {
-                    Screens::Main => {
-                        self.current_screen = Screens::Pomodoro;
-                        self.is_pomodoro_running = true;
-                    }
-                    Screens::Pomodoro => self.is_pomodoro_running = !self.is_pomodoro_running,
-                    _ => {}
-                },
+    // The rest of each turn of the event loop, after any input
+    fn step(&mut self, clock: &impl Clock) {
+        // The timer reads the clock, so ticking often costs nothing but
+        // keeps the display within a poll interval of the real time
+        self.on_tick(clock.instant(), clock.timestamp());
+        let requests: Vec<Request> = self.requests.iter().flat_map(Receiver::try_iter).collect();
+        for request in requests {
+            self.answer(request, clock.timestamp());
+        }
+        self.write_status(clock.timestamp());
+        if tui::interrupted() {
+            self.is_running = false;
+        }
+    }
 
-                KeyCode::Esc => match self.current_screen {
-                    Screens::Pomodoro => {
-                        self.is_pomodoro_running = false;
-                        self.current_screen = Screens::Main;
-                    }
-                    Screens::Quit => {
-                        self.current_screen = Screens::Main;
+    fn answer(&mut self, request: Request, now: i64) {
+        let reply = match self.run_command(request.command, now) {
+            Ok(reply) => reply,
+            Err(err) => format!("error: {err}"),
+        };
+        request.reply(reply);
+    }
+
+    fn shut_down(&mut self) {
+        self.save_state();
+        if let Some(path) = &self.status_path {
+            let _ = status::clear(path);
+        }
+    }
+
+    fn check_keys(&mut self, key: KeyEvent) {
+        if key.kind == KeyEventKind::Release {
+            return;
+        }
+        // Raw mode turns Ctrl+C into a key press instead of SIGINT
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.is_running = false;
+            return;
+        }
+        // Any key silences a repeating alert, and does nothing else
+        if self.alert_active {
+            self.alert_active = false;
+            self.banner = None;
+            if let Some(player) = &self.player {
+                player.stop_alert();
+            }
+            return;
+        }
+        if self.current_screen == Screens::TaskInput {
+            self.edit_task(key);
+            return;
+        }
+        if self.current_screen == Screens::Interruption {
+            self.edit_interruption(key);
+            return;
+        }
+        if self.current_screen == Screens::Suspended {
+            self.answer_suspend(key.code);
+            return;
+        }
+        // The quit prompt only answers to its own keys, so nothing can be
+        // stacked on top of it or changed behind it
+        let action = self.config.keys.action(key.code);
+        if self.current_screen == Screens::Quit
+            && !matches!(
+                (action, key.code),
+                (Some(Actions::Quit), _) | (_, KeyCode::Esc)
+            )
+        {
+            return;
+        }
+        match (action, key.code) {
+            // The task list's own keys, which rebound actions mustn't shadow
+            (_, KeyCode::Char('a')) if self.current_screen == Screens::Tasks => {
+                self.task_input.clear();
+                self.open_screen(Screens::TaskInput);
+            }
+
+            (_, KeyCode::Char('x')) if self.current_screen == Screens::Tasks => {
+                if let Some(task) = self.tasks.get_mut(self.tasks_index) {
+                    task.done = !task.done;
+                    self.save_tasks();
+                }
+            }
+
+            (_, KeyCode::Delete)
+                if self.current_screen == Screens::Tasks && self.tasks_index < self.tasks.len() =>
+            {
+                self.tasks.remove(self.tasks_index);
+                self.tasks_index = self.tasks_index.min(self.tasks.len().saturating_sub(1));
+                self.save_tasks();
+            }
+
+            (Some(Actions::Quit), _) => match self.current_screen {
+                Screens::Quit => self.is_running = false,
+                _ => self.open_screen(Screens::Quit),
+            },
+
+            (Some(Actions::StartPause), _) => match self.current_screen {
+                Screens::Main => {
+                    self.current_screen = Screens::Pomodoro;
+                    self.set_running(true);
+                }
+                Screens::Pomodoro => match self.focus_lock_remaining() {
+                    Some(seconds) if self.timer.is_running() => {
+                        self.show_banner(format!("Locked for {} more min", seconds.div_ceil(60)))
                     }
-                    _ => {}
+                    _ => self.set_running(!self.timer.is_running()),
                 },
+                Screens::Finished => {
+                    self.reset_session();
+                    self.current_screen = Screens::Pomodoro;
+                    self.set_running(true);
+                }
+                Screens::Stats
+                | Screens::Settings
+                | Screens::TaskInput
+                | Screens::Interruption
+                | Screens::Tasks
+                | Screens::Help
+                | Screens::Quit
+                | Screens::Suspended => {}
+            },
 
-                KeyCode::Backspace => {
-                    if let Screens::Pomodoro = self.current_screen {
-                        self.elapsed_seconds = 0;
-                    }
+            // Counts as completing the phase, just without the sound
+            (Some(Actions::Skip), _) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.skip_phase();
                 }
+            }
 
-                KeyCode::Enter => {
-                    match self.current_type {
-                        Pomodoros::Pomodoro => self.current_type = Pomodoros::ShortBreak,
-                        Pomodoros::ShortBreak => self.current_type = Pomodoros::LongBreak,
-                        Pomodoros::LongBreak => self.current_type = Pomodoros::Pomodoro,
-                    }
-                    self.elapsed_seconds = 0;
-                    self.is_pomodoro_running = false;
+            (Some(Actions::Stats), _) => {
+                if let Screens::Main | Screens::Pomodoro = self.current_screen {
+                    self.open_screen(Screens::Stats);
                 }
+            }
+
+            (Some(Actions::Reset), _) | (_, KeyCode::Backspace) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.timer.restart_phase();
+                }
+            }
+
+            (Some(Actions::RestartSession), _) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.reset_session();
+                }
+            }
+
+            (Some(Actions::Mute), _) => {
+                self.config.muted = !self.config.muted;
+                let _ = config::save_value(
+                    self.config.profile.as_deref(),
+                    "muted",
+                    &self.config.muted.to_string(),
+                );
+            }
+
+            (Some(Actions::Help), _) => match self.current_screen {
+                Screens::Help => self.close_screen(),
+                _ => {
+                    self.help_scroll = 0;
+                    self.open_screen(Screens::Help);
+                }
+            },
+
+            (Some(Actions::Task), _) => {
+                if let Screens::Main = self.current_screen {
+                    self.task_input = self.task.clone().unwrap_or_default();
+                    self.open_screen(Screens::TaskInput);
+                } else if self.current_screen == Screens::Pomodoro
+                    && self.timer.current_phase() == Pomodoros::Pomodoro
+                {
+                    self.interruption_note.clear();
+                    self.open_screen(Screens::Interruption);
+                }
+            }
+
+            (Some(Actions::Settings), _) => {
+                if let Screens::Main = self.current_screen {
+                    self.open_settings();
+                }
+            }
+
+            (Some(Actions::TaskList), _) => {
+                if let Screens::Main = self.current_screen {
+                    self.tasks_index = 0;
+                    self.open_screen(Screens::Tasks);
+                }
+            }
+
+            // The arrows work whatever the movement keys are bound to
+            (Some(Actions::Up), _) | (_, KeyCode::Up) => {
+                if let Screens::Settings = self.current_screen {
+                    self.settings_index = self.settings_index.saturating_sub(1);
+                } else if let Screens::Tasks = self.current_screen {
+                    self.tasks_index = self.tasks_index.saturating_sub(1);
+                } else if let Screens::Help = self.current_screen {
+                    self.help_scroll = self.help_scroll.saturating_sub(1);
+                }
+            }
+
+            (Some(Actions::Down), _) | (_, KeyCode::Down) => {
+                if let Screens::Settings = self.current_screen {
+                    self.settings_index = (self.settings_index + 1).min(THEME_ROW);
+                } else if let Screens::Tasks = self.current_screen {
+                    self.tasks_index =
+                        (self.tasks_index + 1).min(self.tasks.len().saturating_sub(1));
+                } else if let Screens::Help = self.current_screen {
+                    // Held back to the end of the text when drawn
+                    self.help_scroll = self.help_scroll.saturating_add(1);
+                }
+            }
+
+            (Some(Actions::Left), _) | (_, KeyCode::Left) => {
+                if let Screens::Settings = self.current_screen {
+                    self.adjust_setting(-1);
+                }
+            }
+
+            (Some(Actions::Right), _) | (_, KeyCode::Right) => {
+                if let Screens::Settings = self.current_screen {
+                    self.adjust_setting(1);
+                }
+            }
+
+            (_, KeyCode::Esc) => match self.current_screen {
+                Screens::Pomodoro => {
+                    self.set_running(false);
+                    self.current_screen = Screens::Main;
+                }
+                // Cancelling drops the theme being previewed too
+                Screens::Settings => {
+                    self.config.theme = self.settings_theme;
+                    self.close_screen();
+                }
+                Screens::Stats
+                | Screens::TaskInput
+                | Screens::Interruption
+                | Screens::Tasks
+                | Screens::Help
+                | Screens::Quit
+                | Screens::Suspended => self.close_screen(),
+                Screens::Finished => {
+                    self.reset_session();
+                    self.current_screen = Screens::Main;
+                }
+                Screens::Main => {}
+            },
+
+            (Some(Actions::ToggleDots), _) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.config.show_dots = !self.config.show_dots;
+                    let _ = config::save_value(
+                        self.config.profile.as_deref(),
+                        "show_dots",
+                        &self.config.show_dots.to_string(),
+                    );
+                }
+            }
+
+            (Some(Actions::FocusMode), _) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.config.focus_mode = !self.config.focus_mode;
+                    let _ = config::save_value(
+                        self.config.profile.as_deref(),
+                        "focus_mode",
+                        &self.config.focus_mode.to_string(),
+                    );
+                }
+            }
+
+            (Some(Actions::VolumeDown), _) => self.adjust_volume(-1.0),
+
+            (Some(Actions::VolumeUp), _) => self.adjust_volume(1.0),
+
+            (Some(Actions::NextTheme), _) => {
+                self.config.theme = self.config.theme.next();
+                let _ = config::save_value(
+                    self.config.profile.as_deref(),
+                    "theme",
+                    &format!("\"{}\"", self.config.theme.name()),
+                );
+                self.show_banner(format!("Theme: {}", self.config.theme.name()));
+            }
 
-                _ => {}
+            (Some(Actions::ToggleClock), _) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.show_remaining = !self.show_remaining;
+                }
+            }
+
+            (Some(Actions::Longer), _) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.adjust_phase(60);
+                } else if let Screens::Settings = self.current_screen {
+                    self.adjust_setting(1);
+                } else if let Screens::Tasks = self.current_screen {
+                    self.adjust_estimate(1);
+                }
+            }
+
+            (Some(Actions::Shorter), _) => {
+                if let Screens::Pomodoro = self.current_screen {
+                    self.adjust_phase(-60);
+                } else if let Screens::Settings = self.current_screen {
+                    self.adjust_setting(-1);
+                } else if let Screens::Tasks = self.current_screen {
+                    self.adjust_estimate(-1);
+                }
+            }
+
+            (_, KeyCode::Enter) if self.current_screen == Screens::Settings => {
+                self.save_settings();
+                self.close_screen();
+            }
+
+            (_, KeyCode::Enter) if self.current_screen == Screens::Tasks => {
+                if let Some(task) = self.tasks.get(self.tasks_index) {
+                    self.task = Some(task.name.clone());
+                    self.close_screen();
+                }
             }
+
+            (_, KeyCode::Enter) => {
+                let cooldown = !self.timer.duration(Pomodoros::Cooldown).is_zero();
+                let next = match self.timer.current_phase() {
+                    Pomodoros::Pomodoro => Pomodoros::ShortBreak,
+                    Pomodoros::ShortBreak => Pomodoros::LongBreak,
+                    Pomodoros::LongBreak if cooldown => Pomodoros::Cooldown,
+                    Pomodoros::LongBreak | Pomodoros::Cooldown => Pomodoros::Pomodoro,
+                };
+                self.set_running(false);
+                self.timer.set_phase(next);
+            }
+
+            _ => {}
         }
-        Ok(())
+    }
+
+    // Screens opened on top of others (stats, help, the quit prompt) remember where
+    // they came from so closing them returns there.
+    fn open_screen(&mut self, screen: Screens) {
+        if self.current_screen == Screens::Pomodoro
+            && self.timer.is_running()
+            && self.config.background_timer == BackgroundModes::AutoPause
+        {
+            self.set_running(false);
+            self.paused_in_background = true;
+        }
+        self.screen_stack.push(self.current_screen);
+        self.current_screen = screen;
+    }
+
+    // Typing on the task screen: Enter keeps the label (an empty one clears
+    // it), Esc leaves the old one in place. Control characters are dropped
+    // since the label is drawn as-is and written to the history.
+    fn edit_task(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c)
+                if !key.modifiers.contains(KeyModifiers::CONTROL) && !c.is_control() =>
+            {
+                self.task_input.push(c)
+            }
+            KeyCode::Backspace => {
+                self.task_input.pop();
+            }
+            KeyCode::Enter => {
+                let task = self.task_input.trim().to_string();
+                // Every label set here also lands in the task list
+                if !task.is_empty() && !self.tasks.iter().any(|listed| listed.name == task) {
+                    self.tasks.push(Task::new(task.clone()));
+                    self.tasks_index = self.tasks.len() - 1;
+                    self.save_tasks();
+                }
+                self.task = (!task.is_empty()).then_some(task);
+                self.close_screen();
+            }
+            KeyCode::Esc => self.close_screen(),
+            _ => {}
+        }
+    }
+
+    // Typing up an interruption: Tab switches between internal and external,
+    // Enter logs it with whatever note was typed, Esc drops it
+    fn edit_interruption(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Tab => self.interruption_kind = self.interruption_kind.toggled(),
+            KeyCode::Char(c)
+                if !key.modifiers.contains(KeyModifiers::CONTROL) && !c.is_control() =>
+            {
+                self.interruption_note.push(c)
+            }
+            KeyCode::Backspace => {
+                self.interruption_note.pop();
+            }
+            KeyCode::Enter => {
+                self.log_interruption();
+                self.close_screen();
+            }
+            KeyCode::Esc => self.close_screen(),
+            _ => {}
+        }
+    }
+
+    fn log_interruption(&mut self) {
+        self.interruptions += 1;
+        let interruption = Interruption {
+            timestamp: calendar::now(),
+            kind: self.interruption_kind,
+            note: self.interruption_note.trim().to_string(),
+        };
+        if let Some(Err(err)) = self
+            .interruptions_path
+            .as_ref()
+            .map(|path| history::append_interruption(path, &interruption))
+        {
+            self.show_banner(format!("Could not log the interruption: {err}"));
+        }
+    }
+
+    fn open_settings(&mut self) {
+        self.settings_draft = [
+            self.config.pomodoro_minutes,
+            self.config.short_break_minutes,
+            self.config.long_break_minutes,
+            self.config.long_break_interval,
+        ];
+        self.settings_index = 0;
+        self.settings_theme = self.config.theme;
+        self.open_screen(Screens::Settings);
+    }
+
+    // Changes the selected setting by `step`, never below its lowest value
+    // nor, for the lengths, past what still fits in seconds. The theme row
+    // switches the theme right away, as a preview.
+    fn adjust_setting(&mut self, step: isize) {
+        if self.settings_index == THEME_ROW {
+            self.config.theme = if step > 0 {
+                self.config.theme.next()
+            } else {
+                self.config.theme.previous()
+            };
+            return;
+        }
+        let (_, _, unit, min) = SETTINGS[self.settings_index];
+        let max = if unit == "min" {
+            usize::MAX / 60
+        } else {
+            usize::MAX
+        };
+        let value = &mut self.settings_draft[self.settings_index];
+        *value = value.saturating_add_signed(step).clamp(min, max);
+    }
+
+    // Applies the edited values right away and writes them to the config file
+    fn save_settings(&mut self) {
+        let [pomodoro, short_break, long_break, long_break_interval] = self.settings_draft;
+        self.config.pomodoro_minutes = pomodoro;
+        self.config.short_break_minutes = short_break;
+        self.config.long_break_minutes = long_break;
+        self.config.long_break_interval = long_break_interval;
+        let minutes = |minutes: usize| Duration::from_secs(minutes.saturating_mul(60) as u64);
+        self.timer
+            .set_duration(Pomodoros::Pomodoro, minutes(pomodoro));
+        self.timer
+            .set_duration(Pomodoros::ShortBreak, minutes(short_break));
+        self.timer
+            .set_duration(Pomodoros::LongBreak, minutes(long_break));
+        self.timer.set_long_break_interval(long_break_interval);
+
+        let profile = self.config.profile.as_deref();
+        let saved = SETTINGS
+            .iter()
+            .zip(self.settings_draft)
+            .try_for_each(|((_, key, _, _), value)| {
+                config::save_value(profile, key, &value.to_string())
+            })
+            .and_then(|()| {
+                let theme = self.config.theme.name();
+                config::save_value(profile, "theme", &format!("\"{theme}\""))
+            });
+        if let Err(err) = saved {
+            self.show_banner(format!("Could not save settings: {err}"));
+        }
+    }
+
+    fn close_screen(&mut self) {
+        self.current_screen = self.screen_stack.pop().unwrap_or(Screens::Main);
+        if self.current_screen == Screens::Pomodoro && self.paused_in_background {
+            self.set_running(true);
+        }
+        self.paused_in_background = false;
     }
 
     fn draw_ui(&mut self, frame: &mut Frame) {
+        self.draw_screen(frame);
+        // Done once everything is drawn, so no widget has to know about it
+        if self.config.ascii || self.config.no_color {
+            ui::strip_colors(frame.buffer_mut());
+        }
+        if self.config.ascii {
+            ui::to_ascii(frame.buffer_mut());
+        }
+    }
+
+    fn draw_screen(&mut self, frame: &mut Frame) {
+        let theme = self.config.palette();
+        self.buttons.clear();
+        let area = frame.area();
+        if self.config.minimal {
+            self.draw_minimal(frame);
+            return;
+        }
+        if area.width < COMPACT_WIDTH || area.height < COMPACT_HEIGHT {
+            self.draw_compact(frame);
+            return;
+        }
+        let (min_width, min_height) = self.current_screen.min_size();
+        if area.width < min_width || area.height < min_height {
+            // The timer and the prompts stay usable, the rest ask for room
+            if matches!(
+                self.current_screen,
+                Screens::Pomodoro | Screens::Quit | Screens::Suspended
+            ) {
+                self.draw_compact(frame);
+                return;
+            }
+            let message = Paragraph::new(vec![
+                Line::styled("Terminal too small", theme.title),
+                Line::raw(format!(
+                    "The {} needs {min_width}x{min_height}",
+                    self.current_screen.name()
+                )),
+                Line::styled("Esc to go back", Style::default().fg(theme.dim)),
+            ])
+            .centered()
+            .wrap(Wrap { trim: true });
+            frame.render_widget(message, centered_rect(100, 50, area));
+            return;
+        }
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(1),
+                Constraint::Length(if self.config.status_bar { 1 } else { 0 }),
+            ])
             .split(frame.area());
 
-        let title_block = Block::default()
+        let mut title_block = Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
+            .border_type(theme.border)
             .style(Style::default());
 
+        if self.player.is_none() {
+            // Playback was never set up, so the timer carries on silently
+            title_block = title_block.title(Line::styled("[no audio]", theme.dim).right_aligned());
+        } else if self.config.muted {
+            title_block = title_block.title(Line::styled("[muted]", theme.dim).right_aligned());
+        } else if self.config.volume < 1.0 {
+            let volume = format!("[vol {:.0}%]", self.config.volume * 100.0);
+            title_block = title_block.title(Line::styled(volume, theme.dim).right_aligned());
+        }
+        let daily_goal = self.config.daily_goal;
+        if daily_goal > 0 {
+            // Looked up by date each time, so it starts over on its own once
+            // the day turns
+            let done = self.today_totals().pomodoros;
+            let color = if done >= daily_goal {
+                theme.success
+            } else {
+                theme.dim
+            };
+            let progress = Line::styled(
+                format!("[{done}/{daily_goal} today]"),
+                Style::default().fg(color),
+            );
+            title_block = title_block.title(progress.left_aligned());
+        }
+
         let title = Paragraph::new(Text::styled(
             "Pomodoro timer",
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.title),
         ))
         .alignment(Alignment::Center)
         .centered()
@@ -171,120 +936,2236 @@ impl App {
 
         frame.render_widget(title, chunks[0]);
 
-        match self.current_screen {
+        // The help is a modal, so what's behind it stays in view
+        let screen = match self.current_screen {
+            Screens::Help => self.screen_stack.last().copied().unwrap_or(Screens::Main),
+            screen => screen,
+        };
+        match screen {
             Screens::Main => {
                 let screen_block = Block::default()
                     .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
+                    .border_type(theme.border)
                     .style(Style::default());
 
+                let keys = &self.config.keys;
+                let key_span = |action| {
+                    let name = keymap::key_name(keys.key(action));
+                    Span::styled(format!(" {name} "), Style::default().fg(theme.accent))
+                };
+
                 let main_span_1 = Span::styled("Press", Style::default());
-                let main_key_span = Span::styled(" Space ", Style::default().fg(Color::Red));
+                let main_key_span = key_span(Actions::StartPause);
                 let main_span_2 = Span::styled("to start round", Style::default());
 
-                let main_text =
-                    Text::from(Line::from(vec![main_span_1, main_key_span, main_span_2]));
+                let stats_span_1 = Span::styled("Press", Style::default());
+                let stats_key_span = key_span(Actions::Stats);
+                let stats_span_2 = Span::styled("to view stats", Style::default());
 
-                let main_paragraph = Paragraph::new(main_text)
-                    .style(Style::default())
-                    .centered()
-                    .block(screen_block);
+                let settings_span_1 = Span::styled("Press", Style::default());
+                let settings_key_span = key_span(Actions::Settings);
+                let settings_span_2 = Span::styled("for settings", Style::default());
+
+                let task_span_1 = Span::styled("Press", Style::default());
+                let task_key_span = key_span(Actions::Task);
+                let task_span_2 = Span::styled("to set a task,", Style::default());
+                let tasks_key_span = key_span(Actions::TaskList);
+                let tasks_span = Span::styled("for the list", Style::default());
 
-                frame.render_widget(main_paragraph, chunks[1]);
+                let help_span_1 = Span::styled("Press", Style::default());
+                let help_key_span = key_span(Actions::Help);
+                let help_span_2 = Span::styled("for help", Style::default());
+
+                let mut main_text = Text::from(vec![
+                    Line::from(vec![main_span_1, main_key_span, main_span_2]),
+                    Line::from(vec![stats_span_1, stats_key_span, stats_span_2]),
+                    Line::from(vec![settings_span_1, settings_key_span, settings_span_2]),
+                    Line::from(vec![
+                        task_span_1,
+                        task_key_span,
+                        task_span_2,
+                        tasks_key_span,
+                        tasks_span,
+                    ]),
+                    Line::from(vec![help_span_1, help_key_span, help_span_2]),
+                ]);
+
+                let today_pomodoros = self.today_totals().pomodoros;
+                let daily_goal = self.config.daily_goal;
+                main_text.push_line(Line::default());
+                if daily_goal > 0 {
+                    main_text.push_line(Line::from(format!(
+                        "Today: {today_pomodoros} / {daily_goal}"
+                    )));
+                } else {
+                    main_text.push_line(Line::from(format!("Today: {today_pomodoros} pomodoros")));
+                }
+
+                // Below the daily goal gauge
+                let mut details_text = Text::default();
+                if daily_goal > 0 && today_pomodoros >= daily_goal {
+                    details_text.push_line(Line::styled(
+                        "Daily goal reached, well done!",
+                        Style::default().fg(theme.success),
+                    ));
+                }
+                if let Some(task) = &self.task {
+                    details_text.push_line(Line::from(format!("Task: {task}")));
+                }
+
+                if let Some(profile) = &self.config.profile {
+                    details_text.push_line(Line::styled(
+                        format!("Profile: {profile}"),
+                        Style::default().fg(theme.dim),
+                    ));
+                }
+
+                if self.config.show_streak {
+                    let today = logical_day(calendar::now(), self.config.day_start_hour);
+                    let goal = self.config.daily_goal;
+                    let streak = stats::streaks(&self.daily_totals, today, goal).current;
+                    let streak_text = match streak {
+                        0 if goal > 1 => "Meet today's goal to start a streak".to_string(),
+                        0 => "Finish a pomodoro today to start a streak".to_string(),
+                        1 => "🔥 1 day — come back tomorrow to keep it going".to_string(),
+                        days => format!("🔥 {days} day streak"),
+                    };
+                    details_text.push_line(Line::default());
+                    details_text
+                        .push_line(Line::styled(streak_text, Style::default().fg(theme.title)));
+                }
+
+                let inner = screen_block.inner(chunks[1]);
+                frame.render_widget(screen_block, chunks[1]);
+                let [main_area, gauge_area, details_area] = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(main_text.height() as u16),
+                        Constraint::Length(if daily_goal > 0 { 1 } else { 0 }),
+                        Constraint::Min(0),
+                    ])
+                    .areas(inner);
+
+                frame.render_widget(Paragraph::new(main_text).centered(), main_area);
+                if daily_goal > 0 {
+                    let ratio = (today_pomodoros as f64 / daily_goal as f64).min(1.0);
+                    let gauge = Gauge::default()
+                        .gauge_style(Style::default().fg(theme.gauge))
+                        .ratio(ratio);
+                    frame.render_widget(gauge, centered_rect(40, 100, gauge_area));
+                }
+                frame.render_widget(Paragraph::new(details_text).centered(), details_area);
             }
             Screens::Pomodoro => {
+                // Everything but the badge is dimmed while paused
+                let paused = !self.timer.is_running();
+                let base_style = if paused {
+                    Style::default().fg(theme.dim)
+                } else {
+                    Style::default()
+                };
+
+                let screen_block = Block::default()
+                    .title(self.phase_title())
+                    .borders(Borders::ALL)
+                    .border_type(theme.border)
+                    .border_style(match self.timer.current_phase() {
+                        _ if paused => base_style,
+                        Pomodoros::Cooldown => Style::default().fg(theme.cooldown),
+                        _ => Style::default(),
+                    })
+                    .style(Style::default());
+
+                let clock = if self.show_remaining {
+                    ui::format_clock(self.timer.remaining().as_secs() as usize)
+                } else {
+                    ui::format_clock(self.elapsed_seconds())
+                };
+                let clock_label = if self.show_remaining {
+                    "Remaining"
+                } else {
+                    "Elapsed"
+                };
+
+                // Focus mode leaves only the phase in the title and the clock
+                let focus_mode = self.config.focus_mode;
+                let mut pomodoro_text = Text::default();
+                if !focus_mode {
+                    // The session's count next to the day's, which outlives restarts
+                    let today_pomodoros = self.today_totals().pomodoros;
+                    pomodoro_text = Text::styled(
+                        format!(
+                            "Pomdoros: {} ({today_pomodoros} today)\nShort breaks: {}\nLong breaks: {}\nFocus: {}",
+                            self.timer.pomodoros(),
+                            self.timer.short_breaks(),
+                            self.timer.long_breaks(),
+                            ui::format_duration(self.focus_seconds()),
+                        ),
+                        base_style,
+                    );
+                    if self.config.show_dots {
+                        pomodoro_text.push_line("•".repeat(self.elapsed_seconds() % 10));
+                    }
+                    pomodoro_text.push_line(Line::styled(self.clock_line(), theme.dim));
+                    if self.interruptions > 0 {
+                        pomodoro_text.push_line(Line::styled(
+                            format!("Interruptions: {}", self.interruptions),
+                            Style::default().fg(theme.warning),
+                        ));
+                    }
+                }
+                let clock_index = pomodoro_text.lines.len().min(4);
+                if let Some(ended) = self.awaiting_start {
+                    let key = keymap::key_name(self.config.keys.key(Actions::StartPause));
+                    pomodoro_text.push_line(Line::styled(
+                        format!(
+                            " {} over — press {key} to start the {} ",
+                            ended.name(),
+                            self.timer.current_phase().name().to_lowercase()
+                        ),
+                        Style::default()
+                            .fg(theme.phase(self.timer.current_phase()))
+                            .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                    ));
+                } else if paused {
+                    pomodoro_text.push_line(Line::styled(
+                        " ⏸ PAUSED ",
+                        Style::default()
+                            .fg(theme.paused)
+                            .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                    ));
+                }
+
+                let content_area = match self.config.layout {
+                    _ if focus_mode => chunks[1],
+                    Layouts::Single => chunks[1],
+                    Layouts::Split => {
+                        let timers_height = if chunks[1].width < SPLIT_MIN_WIDTH {
+                            8
+                        } else {
+                            4
+                        };
+                        let pomodoro_chunks = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([Constraint::Min(1), Constraint::Length(timers_height)])
+                            .split(chunks[1]);
+
+                        self.draw_timers(frame, pomodoro_chunks[1]);
+                        pomodoro_chunks[0]
+                    }
+                };
+                let block_inner = screen_block.inner(content_area);
+                frame.render_widget(screen_block, content_area);
+                let timeline_height = if focus_mode || self.session_phases.is_empty() {
+                    0
+                } else {
+                    1
+                };
+                // The split layout has its own gauge under the countdown
+                let gauge_height = match self.config.layout {
+                    _ if focus_mode => 0,
+                    Layouts::Single => 1,
+                    Layouts::Split => 0,
+                };
+                let [inner, gauge_area, timeline_area, buttons_area] = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(1),
+                        Constraint::Length(gauge_height),
+                        Constraint::Length(timeline_height),
+                        Constraint::Length(1),
+                    ])
+                    .areas(block_inner);
+                if gauge_height > 0 {
+                    let gauge_color = if paused { theme.dim } else { theme.gauge };
+                    frame.render_widget(
+                        Gauge::default()
+                            .gauge_style(Style::default().fg(gauge_color))
+                            .ratio(self.timer.fraction_done()),
+                        centered_rect(60, 100, gauge_area),
+                    );
+                }
+                self.draw_timeline(frame, timeline_area);
+                self.draw_buttons(frame, buttons_area);
+
+                // The big digits grow as large as fits with the stats below
+                // them; a cramped window gets the plain text line instead
+                let big_clock = ui::big_text(&clock).and_then(|rows| {
+                    (1..=MAX_CLOCK_SCALE)
+                        .rev()
+                        .map(|scale| ui::scale_text(&rows, scale))
+                        .find(|rows| {
+                            let width = rows[0].chars().count() as u16;
+                            let height = rows.len() as u16 + 1 + pomodoro_text.height() as u16;
+                            width <= inner.width && height <= inner.height
+                        })
+                });
+                match big_clock {
+                    Some(rows) => {
+                        let clock_chunks = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([
+                                Constraint::Length(rows.len() as u16 + 1),
+                                Constraint::Min(1),
+                            ])
+                            .split(inner);
+                        let big_lines: Vec<Line> = rows
+                            .into_iter()
+                            .map(|row| {
+                                Line::styled(row, if paused { theme.dim } else { theme.title })
+                            })
+                            .collect();
+                        frame.render_widget(Paragraph::new(big_lines).centered(), clock_chunks[0]);
+                        frame.render_widget(
+                            Paragraph::new(pomodoro_text).centered(),
+                            clock_chunks[1],
+                        );
+                    }
+                    None => {
+                        let clock_line = Line::raw(format!("{clock_label}: {clock}"));
+                        pomodoro_text.lines.insert(clock_index, clock_line);
+                        frame.render_widget(Paragraph::new(pomodoro_text).centered(), inner);
+                    }
+                }
+            }
+            Screens::Stats => {
                 let screen_block = Block::default()
-                    .title(match self.current_type {
-                        Pomodoros::Pomodoro => "Pomodoro",
-                        Pomodoros::ShortBreak => "Short break",
-                        Pomodoros::LongBreak => "Long break",
+                    .title("Stats")
+                    .borders(Borders::ALL)
+                    .border_type(theme.border)
+                    .style(Style::default());
+
+                let day_start_hour = self.config.day_start_hour;
+                let today = logical_day(calendar::now(), day_start_hour);
+                let week = stats::week_progress(
+                    &self.history,
+                    today,
+                    day_start_hour,
+                    self.config.week_start_day,
+                );
+                let goal = self.config.weekly_goal;
+
+                let mut lines = vec![
+                    Line::from(format!(
+                        "Today: {} pomodoros",
+                        stats::pomodoros_on(&self.history, today, day_start_hour)
+                    )),
+                    Line::from(if goal > 0 {
+                        format!("This week: {}/{}", week.completed, goal)
+                    } else {
+                        format!("This week: {}", week.completed)
+                    }),
+                    Line::styled(
+                        format!(
+                            "Week started {}, {} days left",
+                            week.first_day.name(),
+                            week.days_left
+                        ),
+                        Style::default().fg(theme.dim),
+                    ),
+                ];
+                if goal > 0 && week.completed >= goal {
+                    lines.push(Line::styled(
+                        "Weekly goal reached!",
+                        Style::default().fg(theme.success),
+                    ));
+                }
+                let streaks = stats::streaks(&self.daily_totals, today, self.config.daily_goal);
+                lines.push(Line::from(format!(
+                    "Streak: {} days (best {})",
+                    streaks.current, streaks.best
+                )));
+                let badges: Vec<Span> = stats::achievements(&self.daily_totals, streaks)
+                    .into_iter()
+                    .map(|(name, reached)| {
+                        if reached {
+                            Span::styled(format!(" ★ {name} "), Style::default().fg(theme.success))
+                        } else {
+                            Span::styled(format!(" ☆ {name} "), Style::default().fg(theme.dim))
+                        }
+                    })
+                    .collect();
+                lines.push(Line::from(badges));
+                for (name, today_count) in
+                    stats::task_pomodoros_on(&self.history, today, day_start_hour)
+                {
+                    let listed = self.tasks.iter().find(|task| task.name == name);
+                    let text = match listed {
+                        Some(task) => {
+                            format!("{name}: {today_count} today, {} total", task.progress())
+                        }
+                        None => format!("{name}: {today_count} today"),
+                    };
+                    lines.push(match listed {
+                        Some(task) if task.is_over_estimate() => {
+                            Line::styled(text, Style::default().fg(theme.warning))
+                        }
+                        _ => Line::from(text),
+                    });
+                }
+
+                let inner = screen_block.inner(chunks[1]);
+                frame.render_widget(screen_block, chunks[1]);
+
+                let stats_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(lines.len() as u16),
+                        Constraint::Length(1),
+                        Constraint::Length(1),
+                        Constraint::Min(0),
+                    ])
+                    .split(inner);
+
+                frame.render_widget(Paragraph::new(lines).centered(), stats_chunks[0]);
+
+                if goal > 0 {
+                    let ratio = (week.completed as f64 / goal as f64).min(1.0);
+                    let gauge = Gauge::default()
+                        .gauge_style(Style::default().fg(theme.gauge))
+                        .ratio(ratio);
+                    frame.render_widget(gauge, centered_rect(60, 100, stats_chunks[1]));
+                }
+
+                // Pomodoros on each of the last seven days, today on the right
+                let bars: Vec<Bar> = (today - 6..=today)
+                    .map(|day| {
+                        let count = stats::pomodoros_on(&self.history, day, day_start_hour);
+                        Bar::default()
+                            .value(count as u64)
+                            .label(Line::from(&calendar::weekday(day).name()[..3]))
+                            .style(Style::default().fg(theme.pomodoro))
                     })
+                    .collect();
+                let bar_width = (stats_chunks[3].width.saturating_sub(6) / 7).clamp(1, 5);
+                let chart = BarChart::default()
+                    .data(BarGroup::default().bars(&bars))
+                    .bar_width(bar_width)
+                    .bar_gap(1)
+                    .value_style(Style::default().fg(theme.on_highlight).bg(theme.pomodoro));
+                let chart_width = bar_width * 7 + 6;
+                let chart_area = anchored_rect(
+                    Sizes::Cells(chart_width),
+                    Sizes::Percent(100),
+                    Anchors::Center,
+                    stats_chunks[3],
+                );
+                frame.render_widget(chart, chart_area);
+            }
+            Screens::Settings => {
+                let screen_block = Block::default()
+                    .title("Settings")
                     .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
+                    .border_type(theme.border)
                     .style(Style::default());
 
-                let pomodoro_text = Text::styled(
+                let label_width = SETTINGS
+                    .iter()
+                    .map(|(label, ..)| label.len())
+                    .max()
+                    .unwrap_or(0);
+                let row_style = |index| {
+                    if index == self.settings_index {
+                        Style::default().fg(theme.on_highlight).bg(theme.highlight)
+                    } else {
+                        Style::default()
+                    }
+                };
+                let mut settings_lines: Vec<Line> = SETTINGS
+                    .iter()
+                    .zip(self.settings_draft)
+                    .enumerate()
+                    .map(|(index, ((label, _, unit, _), value))| {
+                        Line::styled(
+                            format!(" {label:<label_width$}  ◀ {value:>3} ▶ {unit} "),
+                            row_style(index),
+                        )
+                    })
+                    .collect();
+                settings_lines.push(Line::styled(
                     format!(
-                        "Pomdoros: {}\nShort breaks: {}\nLong breaks: {}\nElapsed time: {}m {}s\n{}\n{}",
-                        self.pomdoros,
-                        self.short_breaks,
-                        self.long_breaks,
-                        // (self.elapsed_seconds / 60) as usize,
-                        self.elapsed_seconds / 60,
-                        self.elapsed_seconds%60,
-                        "•".repeat(self.elapsed_seconds % 10),
-                        {
-                            if self.is_pomodoro_running == false {
-                                "Paused"
-                            }else {
-                                ""
-                            }
-                        }
+                        " {:<label_width$}  ◀ {} ▶ ",
+                        "Theme",
+                        self.config.theme.name()
                     ),
-                    Style::default(),
-                );
+                    row_style(THEME_ROW),
+                ));
+                settings_lines.push(Line::default());
+                let key = |action| keymap::key_name(self.config.keys.key(action));
+                settings_lines.push(Line::styled(
+                    format!(
+                        "{}/{} select  {}/{} or {}/{} change  Enter save  Esc cancel",
+                        key(Actions::Up),
+                        key(Actions::Down),
+                        key(Actions::Left),
+                        key(Actions::Right),
+                        key(Actions::Longer),
+                        key(Actions::Shorter)
+                    ),
+                    Style::default().fg(theme.dim),
+                ));
 
-                let pomodoro_paragraph = Paragraph::new(pomodoro_text)
+                let settings_paragraph = Paragraph::new(settings_lines)
                     .style(Style::default())
-                    .centered()
                     .block(screen_block);
 
-                frame.render_widget(pomodoro_paragraph, chunks[1]);
+                frame.render_widget(settings_paragraph, chunks[1]);
             }
-            Screens::Quit => {
+            Screens::TaskInput => {
                 let screen_block = Block::default()
-                    .borders(Borders::NONE)
+                    .title("What are you working on?")
+                    .borders(Borders::ALL)
+                    .border_type(theme.border)
                     .style(Style::default());
 
-                let quit_text = Line::styled("Do you really want to quit?", Style::default());
-                let quit_keys_text = Line::styled("(q/Esc)", Style::default().fg(Color::Red));
+                let task_lines = vec![
+                    Line::from(vec![
+                        Span::raw(self.task_input.as_str()),
+                        Span::styled("█", theme.accent),
+                    ]),
+                    Line::default(),
+                    Line::styled(
+                        "Enter to keep it (empty clears it), Esc to cancel",
+                        Style::default().fg(theme.dim),
+                    ),
+                ];
 
-                let quit_paragraph = Paragraph::new(Text::from(vec![quit_text, quit_keys_text]))
-                    .style(Style::default())
-                    .centered()
+                let task_paragraph = Paragraph::new(task_lines)
+                    .wrap(Wrap { trim: false })
                     .block(screen_block);
 
-                let area = centered_rect(60, 25, frame.area());
-
-                frame.render_widget(quit_paragraph, area);
+                frame.render_widget(task_paragraph, chunks[1]);
             }
-        }
-    }
-
-    fn on_tick(&mut self) {
-        if self.is_pomodoro_running {
-            self.elapsed_seconds += 1;
-        }
-        match self.current_type {
-            Pomodoros::Pomodoro => {
-                if self.elapsed_seconds >= self.pomodoro_time {
-                    play_timer_sound();
-                    self.pomdoros += 1;
-                    self.elapsed_seconds = 0;
+            Screens::Interruption => {
+                let screen_block = Block::default()
+                    .title("Interruption")
+                    .borders(Borders::ALL)
+                    .border_type(theme.border)
+                    .style(Style::default());
 
-                    if self.short_breaks == self.short_breaks_before_long {
-                        self.current_type = Pomodoros::LongBreak;
-                        return;
+                let kind = |kind: Interruptions, label| {
+                    if kind == self.interruption_kind {
+                        Span::styled(
+                            label,
+                            Style::default()
+                                .fg(theme.accent)
+                                .add_modifier(Modifier::REVERSED),
+                        )
+                    } else {
+                        Span::styled(label, Style::default().fg(theme.dim))
                     }
-                    self.current_type = Pomodoros::ShortBreak;
-                }
-            }
-            Pomodoros::ShortBreak => {
-                if self.elapsed_seconds >= self.short_break_time {
-                    play_timer_sound();
-                    self.short_breaks += 1;
-                    self.elapsed_seconds = 0;
-                    self.current_type = Pomodoros::Pomodoro;
-                }
-            }
-            Pomodoros::LongBreak => {
-                if self.elapsed_seconds >= self.long_break_time {
-                    play_timer_sound();
-                    self.long_breaks += 1;
-                    self.elapsed_seconds = 0;
-                    self.current_type = Pomodoros::Pomodoro;
-                }
+                };
+                let lines = vec![
+                    Line::from(vec![
+                        kind(Interruptions::Internal, " internal "),
+                        Span::raw(" "),
+                        kind(Interruptions::External, " external "),
+                    ]),
+                    Line::from(vec![
+                        Span::raw(self.interruption_note.as_str()),
+                        Span::styled("█", theme.accent),
+                    ]),
+                    Line::styled(
+                        "Tab switches, Enter logs it, Esc cancels",
+                        Style::default().fg(theme.dim),
+                    ),
+                ];
+
+                let area = anchored_rect(
+                    Sizes::Percent(60),
+                    Sizes::Cells(lines.len() as u16 + 2),
+                    Anchors::Center,
+                    chunks[1],
+                );
+                let interruption_paragraph = Paragraph::new(lines)
+                    .wrap(Wrap { trim: false })
+                    .block(screen_block);
+
+                frame.render_widget(Clear, area);
+                frame.render_widget(interruption_paragraph, area);
+            }
+            Screens::Tasks => {
+                let screen_block = Block::default()
+                    .title("Tasks")
+                    .borders(Borders::ALL)
+                    .border_type(theme.border)
+                    .style(Style::default());
+
+                let mut task_lines: Vec<Line> = self
+                    .tasks
+                    .iter()
+                    .enumerate()
+                    .map(|(index, task)| {
+                        let marker = if index == self.tasks_index {
+                            "> "
+                        } else {
+                            "  "
+                        };
+                        let check = if task.done { "✔ " } else { "  " };
+                        let style = if task.done {
+                            Style::default().fg(theme.dim)
+                        } else if task.is_over_estimate() {
+                            Style::default().fg(theme.warning)
+                        } else if self.task.as_ref() == Some(&task.name) {
+                            Style::default().fg(theme.accent)
+                        } else {
+                            Style::default()
+                        };
+                        Line::styled(
+                            format!("{marker}{check}{} ({})", task.name, task.progress()),
+                            style,
+                        )
+                    })
+                    .collect();
+                if task_lines.is_empty() {
+                    task_lines.push(Line::styled("No tasks yet", theme.dim));
+                }
+                task_lines.push(Line::default());
+                let key = |action| keymap::key_name(self.config.keys.key(action));
+                task_lines.push(Line::styled(
+                    format!(
+                        "a add · Enter work on it · {}/{} estimate · x done · Del remove · Esc back",
+                        key(Actions::Longer),
+                        key(Actions::Shorter)
+                    ),
+                    Style::default().fg(theme.dim),
+                ));
+
+                let tasks_paragraph = Paragraph::new(task_lines)
+                    .wrap(Wrap { trim: false })
+                    .block(screen_block);
+
+                frame.render_widget(tasks_paragraph, chunks[1]);
+            }
+            // Drawn over the screen it was opened from, below
+            Screens::Help => {}
+            Screens::Finished => {
+                let key = |action| keymap::key_name(self.config.keys.key(action));
+                let screen_block = Block::default()
+                    .title("Session complete")
+                    .borders(Borders::ALL)
+                    .border_type(theme.border)
+                    .border_style(Style::default().fg(theme.success))
+                    .style(Style::default());
+
+                let summary = vec![
+                    Line::styled(
+                        format!(
+                            "{} of {} pomodoros done",
+                            self.timer.pomodoros(),
+                            self.timer.target()
+                        ),
+                        Style::default().fg(theme.success),
+                    ),
+                    Line::raw(format!(
+                        "Focus time: {}",
+                        ui::format_duration(self.focus_seconds())
+                    )),
+                    Line::raw(format!(
+                        "Short breaks: {}  Long breaks: {}",
+                        self.timer.short_breaks(),
+                        self.timer.long_breaks()
+                    )),
+                    Line::default(),
+                    Line::from(vec![
+                        Span::styled(key(Actions::StartPause), Style::default().fg(theme.accent)),
+                        Span::raw(" to go again, "),
+                        Span::styled("Esc", Style::default().fg(theme.accent)),
+                        Span::raw(" for the menu, "),
+                        Span::styled(key(Actions::Quit), Style::default().fg(theme.accent)),
+                        Span::raw(" to quit"),
+                    ]),
+                ];
+
+                let summary_paragraph = Paragraph::new(summary).centered().block(screen_block);
+
+                frame.render_widget(summary_paragraph, chunks[1]);
+            }
+            Screens::Quit => {
+                let screen_block = Block::default()
+                    .borders(Borders::NONE)
+                    .style(Style::default());
+
+                let quit_text = Line::styled("Do you really want to quit?", Style::default());
+                let quit_keys_text = Line::styled(
+                    format!("({})", self.quit_keys()),
+                    Style::default().fg(theme.accent),
+                );
+                let focus_text = Line::styled(
+                    format!(
+                        "Focused {} this session",
+                        ui::format_duration(self.focus_seconds())
+                    ),
+                    Style::default().fg(theme.dim),
+                );
+
+                let quit_paragraph =
+                    Paragraph::new(Text::from(vec![quit_text, quit_keys_text, focus_text]))
+                        .style(Style::default())
+                        .centered()
+                        .block(screen_block);
+
+                let area = anchored_rect(
+                    Sizes::Percent(self.config.quit_prompt_width),
+                    Sizes::Percent(self.config.quit_prompt_height),
+                    self.config.quit_prompt_position,
+                    frame.area(),
+                );
+
+                frame.render_widget(quit_paragraph, area);
+            }
+            Screens::Suspended => {
+                let screen_block = Block::default()
+                    .title("Welcome back")
+                    .borders(Borders::ALL)
+                    .border_type(theme.border)
+                    .style(Style::default());
+
+                let key = |key| Span::styled(key, Style::default().fg(theme.accent));
+                let lines = vec![
+                    Line::from(format!(
+                        "The computer slept for {} during the {}",
+                        ui::format_duration(self.slept),
+                        self.timer.current_phase().name().to_lowercase()
+                    )),
+                    Line::default(),
+                    Line::from(vec![key("c"), Span::raw(" count it towards the phase")]),
+                    Line::from(vec![key("d"), Span::raw(" leave it out")]),
+                    Line::from(vec![key("r"), Span::raw(" start the phase over")]),
+                ];
+
+                let suspend_paragraph = Paragraph::new(lines).centered().block(screen_block);
+
+                frame.render_widget(suspend_paragraph, chunks[1]);
+            }
+        }
+
+        if self.current_screen == Screens::Help {
+            // Nothing behind the modal can be clicked
+            self.buttons.clear();
+            self.draw_help(frame, chunks[1]);
+        }
+
+        if self.config.status_bar {
+            self.draw_status_bar(frame, chunks[2]);
+        }
+
+        if let Some((text, _)) = &self.banner {
+            let banner_block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(theme.border)
+                .style(Style::default().fg(theme.title));
+
+            let banner_paragraph = Paragraph::new(text.as_str()).centered().block(banner_block);
+
+            let area = anchored_rect(
+                Sizes::Cells(text.chars().count() as u16 + 4),
+                Sizes::Cells(3),
+                Anchors::TopRight,
+                frame.area(),
+            );
+
+            frame.render_widget(Clear, area);
+            frame.render_widget(banner_paragraph, area);
+        }
+    }
+
+    // Every key binding, then how the phases follow each other with the
+    // current settings. Scrolls when it doesn't fit.
+    fn draw_help(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.config.palette();
+        let screen_block = Block::default()
+            .title("Help")
+            .title(Line::styled("↑/↓ scroll", theme.dim).right_aligned())
+            .borders(Borders::ALL)
+            .border_type(theme.border)
+            .style(Style::default());
+
+        let bindings: Vec<(String, &str)> = Actions::ALL
+            .into_iter()
+            .map(|action| {
+                let key = keymap::key_name(self.config.keys.key(action));
+                (key, action.description())
+            })
+            .chain(
+                KEY_BINDINGS
+                    .iter()
+                    .map(|(key, description)| (key.to_string(), *description)),
+            )
+            .collect();
+        let key_width = bindings.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+        let mut help_lines: Vec<Line> = bindings
+            .iter()
+            .map(|(key, description)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{key:>key_width$}  "),
+                        Style::default().fg(theme.accent),
+                    ),
+                    Span::styled(*description, Style::default()),
+                ])
+            })
+            .collect();
+
+        let minutes = |phase| self.timer.duration(phase).as_secs() / 60;
+        let mut rules = vec![
+            format!(
+                "A {} min pomodoro is followed by a {} min short break.",
+                minutes(Pomodoros::Pomodoro),
+                minutes(Pomodoros::ShortBreak)
+            ),
+            format!(
+                "Every {} pomodoros the break is a long one, {} min.",
+                self.timer.long_break_interval(),
+                minutes(Pomodoros::LongBreak)
+            ),
+        ];
+        if !self.timer.duration(Pomodoros::Cooldown).is_zero() {
+            rules.push(format!(
+                "A {} min cooldown comes after each long break.",
+                minutes(Pomodoros::Cooldown)
+            ));
+        }
+        if self.timer.target() > 0 {
+            rules.push(format!(
+                "The session ends after {} pomodoros.",
+                self.timer.target()
+            ));
+        }
+        rules.push(if self.config.auto_advance {
+            "Each phase starts as soon as the last one ends.".to_string()
+        } else {
+            "Each phase waits for you to start it.".to_string()
+        });
+        help_lines.push(Line::default());
+        help_lines.push(Line::styled("The cycle", Style::default().fg(theme.title)));
+        help_lines.extend(rules.into_iter().map(Line::from));
+
+        let area = centered_rect(90, 90, area);
+        // Keep the scroll within the text, so scrolling back up is immediate
+        let visible = area.height.saturating_sub(2);
+        let max_scroll = (help_lines.len() as u16).saturating_sub(visible);
+        self.help_scroll = self.help_scroll.min(max_scroll);
+
+        let help_paragraph = Paragraph::new(help_lines)
+            .style(Style::default())
+            .scroll((self.help_scroll, 0))
+            .block(screen_block);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(help_paragraph, area);
+    }
+
+    // The keys that do something on the current screen, as `key what · ...`
+    fn draw_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let theme = self.config.palette();
+        let key = |action| keymap::key_name(self.config.keys.key(action));
+        let fixed = |key: &str| key.to_string();
+        let hints: Vec<(String, &str)> = match self.current_screen {
+            Screens::Main => vec![
+                (key(Actions::StartPause), "start"),
+                (key(Actions::Stats), "stats"),
+                (key(Actions::Settings), "settings"),
+                (key(Actions::Task), "task"),
+                (key(Actions::TaskList), "task list"),
+                (key(Actions::Help), "help"),
+                (key(Actions::Quit), "quit"),
+            ],
+            Screens::Pomodoro => {
+                let mut hints = vec![
+                    (
+                        key(Actions::StartPause),
+                        if self.timer.is_running() {
+                            "pause"
+                        } else {
+                            "resume"
+                        },
+                    ),
+                    (key(Actions::Skip), "skip"),
+                    (key(Actions::Reset), "restart"),
+                ];
+                if self.timer.current_phase() == Pomodoros::Pomodoro {
+                    hints.push((key(Actions::Task), "interruption"));
+                }
+                hints.push((fixed("Esc"), "stop"));
+                hints.push((key(Actions::Quit), "quit"));
+                hints
+            }
+            Screens::Stats | Screens::Help => {
+                vec![(fixed("Esc"), "back"), (key(Actions::Quit), "quit")]
+            }
+            Screens::Settings => vec![
+                (
+                    format!("{}/{}", key(Actions::Up), key(Actions::Down)),
+                    "select",
+                ),
+                (
+                    format!("{}/{}", key(Actions::Left), key(Actions::Right)),
+                    "change",
+                ),
+                (fixed("Enter"), "save"),
+                (fixed("Esc"), "cancel"),
+            ],
+            Screens::TaskInput => vec![(fixed("Enter"), "keep"), (fixed("Esc"), "cancel")],
+            Screens::Interruption => vec![
+                (fixed("Tab"), "internal/external"),
+                (fixed("Enter"), "log"),
+                (fixed("Esc"), "cancel"),
+            ],
+            Screens::Tasks => vec![
+                (fixed("a"), "add"),
+                (fixed("Enter"), "work on it"),
+                (
+                    format!("{}/{}", key(Actions::Longer), key(Actions::Shorter)),
+                    "estimate",
+                ),
+                (fixed("x"), "done"),
+                (fixed("Esc"), "back"),
+            ],
+            Screens::Finished => vec![
+                (key(Actions::StartPause), "new session"),
+                (fixed("Esc"), "main screen"),
+                (key(Actions::Quit), "quit"),
+            ],
+            Screens::Quit => vec![(key(Actions::Quit), "quit"), (fixed("Esc"), "stay")],
+            Screens::Suspended => vec![
+                (fixed("c"), "count"),
+                (fixed("d"), "leave out"),
+                (fixed("r"), "restart"),
+            ],
+        };
+
+        let mut spans = Vec::new();
+        for (index, (key, what)) in hints.into_iter().enumerate() {
+            if index > 0 {
+                spans.push(Span::styled(" · ", Style::default().fg(theme.dim)));
+            }
+            spans.push(Span::styled(key, Style::default().fg(theme.accent)));
+            spans.push(Span::styled(
+                format!(" {what}"),
+                Style::default().fg(theme.dim),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)).centered(), area);
+    }
+
+    // The phase and the time left on two centered lines (one if that's all
+    // there is), for terminals too small for anything else
+    fn draw_compact(&self, frame: &mut Frame) {
+        let theme = self.config.palette();
+        let accent = Style::default().fg(theme.accent);
+        let (first, second) = match self.current_screen {
+            Screens::Quit => (Span::raw("Quit?"), Span::styled(self.quit_keys(), accent)),
+            Screens::Suspended => (
+                Span::raw(format!("Slept {}", ui::format_duration(self.slept))),
+                Span::styled("c/d/r", accent),
+            ),
+            _ => {
+                let phase = self.timer.current_phase();
+                let clock_style = if self.timer.is_running() {
+                    Style::default().fg(theme.title)
+                } else {
+                    Style::default().fg(theme.dim)
+                };
+                let clock = ui::format_clock(self.timer.remaining().as_secs() as usize);
+                (
+                    Span::styled(phase.name(), Style::default().fg(theme.phase(phase))),
+                    Span::styled(clock, clock_style),
+                )
+            }
+        };
+
+        let area = frame.area();
+        let lines = if area.height < 2 {
+            vec![Line::from(vec![first, Span::raw(" "), second])]
+        } else {
+            vec![Line::from(first), Line::from(second)]
+        };
+        let top = area.height.saturating_sub(lines.len() as u16) / 2;
+        let text_area = Rect {
+            y: area.y + top,
+            height: area.height - top,
+            ..area
+        };
+        frame.render_widget(Paragraph::new(lines).centered(), text_area);
+    }
+
+    // The keys that answer the quit prompt
+    fn quit_keys(&self) -> String {
+        format!(
+            "{}/Esc",
+            keymap::key_name(self.config.keys.key(Actions::Quit))
+        )
+    }
+
+    fn draw_minimal(&self, frame: &mut Frame) {
+        let theme = self.config.palette();
+        let line = match self.current_screen {
+            Screens::Quit => Line::from(vec![
+                Span::raw("Quit? "),
+                Span::styled(self.quit_keys(), Style::default().fg(theme.accent)),
+            ]),
+            Screens::Suspended => Line::from(vec![
+                Span::raw(format!("Slept {}: ", ui::format_duration(self.slept))),
+                Span::styled("c/d/r", Style::default().fg(theme.accent)),
+            ]),
+            _ => {
+                let icon = ui::phase_icon(self.timer.current_phase());
+                let clock = if self.show_remaining {
+                    ui::format_clock(self.timer.remaining().as_secs() as usize)
+                } else {
+                    ui::format_clock(self.elapsed_seconds())
+                };
+                let state = match self.current_screen {
+                    Screens::Finished => "✔",
+                    _ if self.timer.is_running() => "▶",
+                    _ => "⏸",
+                };
+                let count = match self.timer.target() {
+                    0 => self.timer.pomodoros().to_string(),
+                    target => format!("{}/{target}", self.timer.pomodoros()),
+                };
+                let clock_style = if self.timer.is_running() {
+                    Style::default().fg(theme.title)
+                } else {
+                    Style::default().fg(theme.dim)
+                };
+                Line::from(vec![
+                    Span::raw(format!("{icon} ")),
+                    Span::styled(clock, clock_style),
+                    Span::raw(format!(" {state} {count}")),
+                ])
+            }
+        };
+        frame.render_widget(Paragraph::new(line), frame.area());
+    }
+
+    // A block per finished phase, the latest ones if they don't all fit
+    fn draw_timeline(&self, frame: &mut Frame, area: Rect) {
+        let theme = self.config.palette();
+        let fits = (area.width as usize).div_ceil(2);
+        let shown = &self.session_phases[self.session_phases.len().saturating_sub(fits)..];
+        let blocks: Vec<Span> = shown
+            .iter()
+            .map(|&phase| Span::styled("█ ", Style::default().fg(theme.phase(phase))))
+            .collect();
+        frame.render_widget(Paragraph::new(Line::from(blocks)), area);
+    }
+
+    // A row of clickable buttons, remembered so clicks can be matched to them
+    fn draw_buttons(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.config.palette();
+        let start = if self.timer.is_running() {
+            " Pause "
+        } else {
+            " Start "
+        };
+        let keys = &self.config.keys;
+        let buttons = [
+            (Button::new(start, theme), keys.key(Actions::StartPause)),
+            (Button::new(" Skip ", theme), keys.key(Actions::Skip)),
+            (Button::new(" Reset ", theme), keys.key(Actions::Reset)),
+            (Button::new(" Quit ", theme), keys.key(Actions::Quit)),
+        ];
+
+        let gap = 2;
+        let width: u16 = buttons
+            .iter()
+            .map(|(button, _)| button.width() + gap)
+            .sum::<u16>()
+            - gap;
+        let mut x = area.x + area.width.saturating_sub(width) / 2;
+        for (button, key) in buttons {
+            let button_width = button.width();
+            let button_area = Rect::new(x, area.y, button_width, 1).intersection(area);
+            let hovered = self
+                .mouse_position
+                .is_some_and(|position| button_area.contains(position));
+            frame.render_widget(button.hovered(hovered), button_area);
+            self.buttons.push((button_area, key));
+            x += button_width + gap;
+        }
+    }
+
+    // Pauses the timer while the terminal is unfocused when so configured,
+    // resuming it on return only if it was the one that paused it
+    fn focus_changed(&mut self, focused: bool) {
+        if self.current_screen != Screens::Pomodoro
+            || self.config.unfocused_timer != BackgroundModes::AutoPause
+        {
+            return;
+        }
+        if !focused && self.timer.is_running() {
+            self.set_running(false);
+            self.paused_while_unfocused = true;
+        } else if focused && self.paused_while_unfocused {
+            self.set_running(true);
+            self.paused_while_unfocused = false;
+        }
+    }
+
+    // A left click on a button does what its key does, and the button under
+    // the mouse is highlighted
+    fn mouse_event(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+        self.mouse_position = Some(position);
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        if let Some(&(_, key)) = self
+            .buttons
+            .iter()
+            .find(|(area, _)| area.contains(position))
+        {
+            self.check_keys(key.into());
+        }
+    }
+
+    /// The phase and the time left for the terminal's title bar or tab, like
+    /// `🍅 14:52 — Pomodoro — write report`.
+    fn window_title(&self) -> String {
+        let clock = ui::format_clock(self.timer.remaining().as_secs() as usize);
+        let mut title = format!("{clock} — {}", self.phase_title());
+        if !self.config.ascii {
+            let icon = ui::phase_icon(self.timer.current_phase());
+            title.insert_str(0, &format!("{icon} "));
+        }
+        if !self.timer.is_running() {
+            title.push_str(" (paused)");
+        }
+        title
+    }
+
+    fn phase_title(&self) -> String {
+        let phase = match self.timer.current_phase() {
+            Pomodoros::Cooldown => "Cooldown — easing back in",
+            phase => phase.name(),
+        };
+        match &self.task {
+            Some(task) => format!("{phase} — {task}"),
+            None => phase.to_string(),
+        }
+    }
+
+    // The time now and when the phase will end. While paused the end is
+    // kept where it was at the moment of pausing.
+    fn clock_line(&self) -> String {
+        let now = calendar::now();
+        let paused = self
+            .paused_since
+            .map_or(0, |since| since.elapsed().as_secs() as i64);
+        let remaining = self.timer.remaining().as_secs() as i64;
+        let ends_at = now - paused + remaining;
+        let twelve_hour = self.config.twelve_hour_clock;
+        format!(
+            "{} · ends {}",
+            ui::format_time_of_day(calendar::minute_of_day(now), twelve_hour),
+            ui::format_time_of_day(calendar::minute_of_day(ends_at), twelve_hour)
+        )
+    }
+
+    // The split layout's phase countdown next to today's total focus time
+    fn draw_timers(&self, frame: &mut Frame, area: Rect) {
+        let theme = self.config.palette();
+        let direction = if area.width < SPLIT_MIN_WIDTH {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        let timer_chunks = Layout::default()
+            .direction(direction)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+
+        let phase_block = Block::default()
+            .title("Remaining")
+            .borders(Borders::ALL)
+            .border_type(theme.border);
+        let phase_area = phase_block.inner(timer_chunks[0]);
+        frame.render_widget(phase_block, timer_chunks[0]);
+
+        let phase_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .split(phase_area);
+        let (clock_style, gauge_color) = if self.timer.is_running() {
+            (Style::default(), theme.gauge)
+        } else {
+            (Style::default().fg(theme.dim), theme.dim)
+        };
+        frame.render_widget(
+            Paragraph::new(ui::format_clock(self.timer.remaining().as_secs() as usize))
+                .style(clock_style)
+                .centered(),
+            phase_chunks[0],
+        );
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(gauge_color))
+                .ratio(self.timer.fraction_done()),
+            phase_chunks[1],
+        );
+
+        let day_start_hour = self.config.day_start_hour;
+        let today = logical_day(calendar::now(), day_start_hour);
+        let mut focus_seconds = stats::focus_seconds_on(&self.history, today, day_start_hour);
+        if let Pomodoros::Pomodoro = self.timer.current_phase() {
+            focus_seconds += self.elapsed_seconds();
+        }
+        let focus_paragraph = Paragraph::new(ui::format_duration(focus_seconds))
+            .centered()
+            .block(
+                Block::default()
+                    .title("Focus today")
+                    .borders(Borders::ALL)
+                    .border_type(theme.border),
+            );
+        frame.render_widget(focus_paragraph, timer_chunks[1]);
+    }
+
+    fn elapsed_seconds(&self) -> usize {
+        self.timer.elapsed().as_secs() as usize
+    }
+
+    // Changes the length of the current phase type. Shortening it below the
+    // time already spent finishes the phase on the next tick.
+    fn adjust_phase(&mut self, seconds: isize) {
+        let phase = self.timer.current_phase();
+        let duration = (self.timer.duration(phase).as_secs() as usize)
+            .saturating_add_signed(seconds)
+            .max(MIN_PHASE_SECONDS);
+        self.timer
+            .set_duration(phase, Duration::from_secs(duration as u64));
+        let minutes = duration / 60;
+        self.show_banner(format!("Phase length: {minutes} min"));
+    }
+
+    // Moves the volume `steps` tenths up or down and saves it
+    fn adjust_volume(&mut self, steps: f32) {
+        let tenths = (self.config.volume * 10.0).round() + steps;
+        self.config.volume = tenths.clamp(0.0, 10.0) / 10.0;
+        if let Some(player) = &self.player {
+            player.set_volume(self.config.volume);
+        }
+        let _ = config::save_value(
+            self.config.profile.as_deref(),
+            "volume",
+            &format!("{:.1}", self.config.volume),
+        );
+        self.show_banner(format!("Volume: {:.0}%", self.config.volume * 100.0));
+    }
+
+    fn on_tick(&mut self, now: Instant, wall_now: i64) {
+        if !self.alert_active
+            && self
+                .banner
+                .as_ref()
+                .is_some_and(|(_, shown_at)| shown_at.elapsed() >= BANNER_DURATION)
+        {
+            self.banner = None;
+        }
+
+        let tick = self.tick_at(now, wall_now);
+        if now.saturating_duration_since(self.state_saved_at) >= STATE_SAVE_INTERVAL {
+            self.state_saved_at = now;
+            self.save_state();
+        }
+
+        if let Some(ended) = tick.ended.filter(|_| !self.config.muted) {
+            match &self.player {
+                Some(player) => {
+                    let repeat = self.config.repeat_alert;
+                    if player.play_timer_sound(ended, &self.config, repeat) && repeat {
+                        self.alert_active = true;
+                        self.show_banner("Time's up — press any key".to_string());
+                    }
+                }
+                // Without an audio device the terminal bell is all that's left
+                None => sound::bell(),
+            }
+        }
+        let ticking = self.config.ticking_enabled
+            && !self.config.muted
+            && self.timer.is_running()
+            && self.timer.current_phase() == Pomodoros::Pomodoro
+            && tick.ended.is_none();
+        if let Some(player) = self.player.as_ref().filter(|_| ticking) {
+            if self.elapsed_seconds() != self.ticked_second {
+                player.tick();
+            }
+        }
+        self.ticked_second = self.elapsed_seconds();
+
+        if let Some(ended) = tick.ended.filter(|_| self.config.notifications_enabled) {
+            let next_phase = self.timer.current_phase().name().to_lowercase();
+            let next = match (self.current_screen, self.config.message_for(ended)) {
+                (Screens::Finished, _) => "the session is done".to_string(),
+                (_, Some(template)) => notify::fill(
+                    template,
+                    ended.name(),
+                    &next_phase,
+                    self.task.as_deref().unwrap_or_default(),
+                ),
+                (_, None) => format!("time for a {next_phase}"),
+            };
+            let summary = match &self.task {
+                Some(task) => format!("{} complete — {task}", ended.name()),
+                None => format!("{} complete", ended.name()),
+            };
+            notify::send(&summary, &next);
+        }
+    }
+
+    // Moves the timer to `now`, recording every phase that ended since the
+    // last tick. The effects (sound, notification, banner) are left to the
+    // caller so this can be driven by synthetic clocks in tests.
+    fn tick_at(&mut self, now: Instant, wall_now: i64) -> Tick {
+        // Instant stops while the machine is suspended but the wall clock
+        // doesn't, so the difference between the two is time spent asleep
+        let dt = now.saturating_duration_since(self.last_tick);
+        let wall_gap = wall_now - self.last_tick_at;
+        let slept = wall_gap - dt.as_secs() as i64;
+        self.last_tick_at = wall_now;
+        self.last_tick = now;
+
+        let mut ended = self.advance(dt);
+        // The time asleep is held back until the suspend prompt is answered
+        let asleep = slept > SLEEP_THRESHOLD_SECONDS && self.timer.is_running();
+        if asleep && self.headless {
+            // Nobody can answer the prompt, so the time asleep just counts
+            ended.extend(self.advance(Duration::from_secs(slept as u64)));
+        } else if asleep {
+            self.set_running(false);
+            self.slept = slept as usize;
+            self.open_screen(Screens::Suspended);
+        }
+
+        Tick {
+            ended: ended.last().copied(),
+        }
+    }
+
+    // Moves the timer `dt` forward and records every phase that ended
+    fn advance(&mut self, dt: Duration) -> Vec<Pomodoros> {
+        let ended = self.timer.tick(dt);
+        for &phase in &ended {
+            self.record_phase(phase);
+        }
+        if !ended.is_empty() && self.timer.is_finished() {
+            self.finish_session();
+        } else if let Some(&last) = ended.last().filter(|_| !self.timer.is_running()) {
+            self.set_running(false);
+            self.awaiting_start = Some(last);
+        }
+        ended
+    }
+
+    // The suspend prompt: count the time asleep towards the phase, drop it,
+    // or start the phase over. The timer carries on in each case.
+    fn answer_suspend(&mut self, code: KeyCode) {
+        let slept = Duration::from_secs(self.slept as u64);
+        match code {
+            KeyCode::Char('c') => {
+                self.close_screen();
+                self.set_running(true);
+                let pomodoros_before = self.timer.pomodoros();
+                self.advance(slept);
+                self.show_banner(format!(
+                    "Counted {} asleep — {} pomodoros done meanwhile",
+                    ui::format_duration(self.slept),
+                    self.timer.pomodoros() - pomodoros_before,
+                ));
+            }
+            KeyCode::Char('d') | KeyCode::Esc => {
+                self.close_screen();
+                self.set_running(true);
+            }
+            KeyCode::Char('r') => {
+                self.close_screen();
+                self.timer.restart_phase();
+                self.set_running(true);
+            }
+            _ => {}
+        }
+    }
+
+    fn set_running(&mut self, running: bool) {
+        self.awaiting_start = None;
+        match (self.paused_since, running) {
+            (Some(_), true) => self.paused_since = None,
+            (None, false) => self.paused_since = Some(Instant::now()),
+            _ => {}
+        }
+        if running {
+            self.timer.resume();
+        } else {
+            self.timer.pause();
+        }
+    }
+
+    // Counts the current phase as done without waiting for it, just without
+    // the sound
+    fn skip_phase(&mut self) {
+        self.awaiting_start = None;
+        let ended = self.timer.skip();
+        self.record_phase(ended);
+        if self.timer.is_finished() {
+            self.finish_session();
+        }
+    }
+
+    fn focus_seconds(&self) -> usize {
+        self.timer.focus_time().as_secs() as usize
+    }
+
+    // Stops the timer and shows the summary once the target is reached
+    fn finish_session(&mut self) {
+        self.set_running(false);
+        self.screen_stack.clear();
+        self.paused_in_background = false;
+        self.current_screen = Screens::Finished;
+    }
+
+    // Clears the counters and the timeline and starts again from the first
+    // pomodoro
+    fn reset_session(&mut self) {
+        self.awaiting_start = None;
+        self.timer.reset();
+        self.session_phases.clear();
+        self.interruptions = 0;
+    }
+
+    // Seconds until a running pomodoro may be paused, if it's still locked
+    fn focus_lock_remaining(&self) -> Option<usize> {
+        let lock = self.config.focus_lock_minutes * 60;
+        let elapsed = self.elapsed_seconds();
+        match self.timer.current_phase() {
+            Pomodoros::Pomodoro if elapsed < lock => Some(lock - elapsed),
+            _ => None,
+        }
+    }
+
+    // Saves the session for the next launch. A finished or untouched one is
+    // nothing to come back to, so the file is removed instead.
+    fn save_state(&self) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+        let progress = self.timer.progress();
+        let untouched = progress.elapsed.is_zero()
+            && progress.pomodoros + progress.short_breaks + progress.long_breaks == 0;
+        let _ = if self.timer.is_finished() || untouched {
+            state::clear(path)
+        } else {
+            state::save(
+                path,
+                &SavedState {
+                    progress,
+                    task: self.task.clone(),
+                },
+            )
+        };
+    }
+
+    // Keeps the status file for `pomodoro-tui status` and the watchers
+    // current. Readers count a running timer down themselves, so it's only
+    // written when that would go wrong: on starts, pauses, new phases and
+    // changed lengths.
+    fn write_status(&mut self, now: i64) {
+        let status = self.status(now);
+        let unchanged = self.written_status.as_ref().is_some_and(|written| {
+            (written.phase, written.running, written.pomodoros, written.target, &written.task)
+                == (status.phase, status.running, status.pomodoros, status.target, &status.task)
+                // Seconds are counted on two clocks, so they may drift apart by one
+                && written.remaining_at(now).abs_diff(status.remaining_seconds) <= 1
+        });
+        if unchanged {
+            return;
+        }
+        if let Some(path) = &self.status_path {
+            let _ = status::save(path, &status);
+        }
+        self.status_watchers
+            .retain(|watcher| watcher.send(status.clone()).is_ok());
+        self.written_status = Some(status);
+    }
+
+    fn status(&self, now: i64) -> Status {
+        Status {
+            phase: self.timer.current_phase(),
+            running: self.timer.is_running(),
+            remaining_seconds: self.timer.remaining().as_secs() as usize,
+            written_at: now,
+            pomodoros: self.timer.pomodoros(),
+            target: self.timer.target(),
+            task: self.task.clone(),
+        }
+    }
+
+    // Carries out a command from the control socket whatever screen is open,
+    // answering with `ok` or, for `status`, the status as JSON
+    fn run_command(&mut self, command: ControlCommands, now: i64) -> Result<String, String> {
+        let in_session = !matches!(self.current_screen, Screens::Main | Screens::Finished);
+        match command {
+            ControlCommands::Start => self.start_from_anywhere(),
+            ControlCommands::Pause => self.pause_unless_locked()?,
+            ControlCommands::Toggle if self.timer.is_running() => self.pause_unless_locked()?,
+            ControlCommands::Toggle => self.start_from_anywhere(),
+            ControlCommands::Skip | ControlCommands::Reset if !in_session => {
+                return Err("no session in progress".to_string());
+            }
+            ControlCommands::Skip => self.skip_phase(),
+            ControlCommands::Reset => self.timer.restart_phase(),
+            ControlCommands::Status => {
+                let status = self.status(now);
+                return Ok(status::render(
+                    Some(&status),
+                    StatusFormats::Json,
+                    now,
+                    false,
+                ));
+            }
+            ControlCommands::Quit => self.is_running = false,
+        }
+        Ok("ok".to_string())
+    }
+
+    // Starts the timer, opening it from the main screen or starting a new
+    // session after a finished one like Space does there. An unanswered
+    // suspend prompt is dismissed, leaving the time asleep out.
+    fn start_from_anywhere(&mut self) {
+        match self.current_screen {
+            Screens::Main => self.current_screen = Screens::Pomodoro,
+            Screens::Finished => {
+                self.reset_session();
+                self.current_screen = Screens::Pomodoro;
+            }
+            Screens::Suspended => self.close_screen(),
+            _ => {}
+        }
+        self.set_running(true);
+    }
+
+    fn pause_unless_locked(&mut self) -> Result<(), String> {
+        if let Some(seconds) = self.focus_lock_remaining() {
+            return Err(format!("locked for {} more min", seconds.div_ceil(60)));
+        }
+        self.set_running(false);
+        Ok(())
+    }
+
+    // Changes the selected task's estimate by `step` pomodoros, 0 being none
+    fn adjust_estimate(&mut self, step: isize) {
+        if let Some(task) = self.tasks.get_mut(self.tasks_index) {
+            task.estimate = task.estimate.saturating_add_signed(step);
+            self.save_tasks();
+        }
+    }
+
+    fn save_tasks(&mut self) {
+        if let Some(Err(err)) = self
+            .tasks_path
+            .as_ref()
+            .map(|path| tasks::save(path, &self.tasks))
+        {
+            self.show_banner(format!("Could not save tasks: {err}"));
+        }
+    }
+
+    pub fn show_banner(&mut self, text: String) {
+        self.banner = Some((text, Instant::now()));
+    }
+
+    fn record_phase(&mut self, phase: Pomodoros) {
+        self.session_phases.push(phase);
+        let active_task = self
+            .tasks
+            .iter_mut()
+            .find(|task| self.task.as_ref() == Some(&task.name));
+        if let Some(task) = active_task.filter(|_| phase == Pomodoros::Pomodoro) {
+            task.pomodoros += 1;
+            self.save_tasks();
+        }
+        let record = Record {
+            timestamp: calendar::now(),
+            phase,
+            planned_seconds: self.timer.duration(phase).as_secs() as usize,
+            interruptions: match phase {
+                Pomodoros::Pomodoro => std::mem::take(&mut self.interruptions),
+                Pomodoros::ShortBreak | Pomodoros::LongBreak | Pomodoros::Cooldown => 0,
+            },
+            task: self.task.clone(),
+        };
+        // Losing a history line is better than interrupting the timer, so
+        // failures are only reported
+        let appended = match &self.history_path {
+            Some(path) => history::append(path, &record),
+            None => Ok(()),
+        };
+        self.history.push(record);
+
+        self.today_totals_mut().add(phase);
+        let daily_goal = self.config.daily_goal;
+        // Fires once a day, as the count only passes the goal once
+        if phase == Pomodoros::Pomodoro
+            && daily_goal > 0
+            && self.today_totals().pomodoros == daily_goal
+        {
+            self.show_banner(format!("Daily goal of {daily_goal} pomodoros reached!"));
+            if self.config.notifications_enabled {
+                notify::send(
+                    "Daily goal reached",
+                    &format!("{daily_goal} pomodoros today"),
+                );
+            }
+        }
+        let saved = match &self.stats_path {
+            Some(path) => stats::save(path, &self.daily_totals),
+            None => Ok(()),
+        };
+
+        if let Err(err) = appended {
+            self.show_banner(format!("Could not write history: {err}"));
+        } else if let Err(err) = saved {
+            self.show_banner(format!("Could not save daily totals: {err}"));
+        }
+    }
+
+    fn today_key(&self) -> String {
+        calendar::date_string(logical_day(calendar::now(), self.config.day_start_hour))
+    }
+
+    fn today_totals(&self) -> DayTotals {
+        self.daily_totals
+            .get(&self.today_key())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn today_totals_mut(&mut self) -> &mut DayTotals {
+        let today = self.today_key();
+        self.daily_totals.entry(today).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, collections::VecDeque, io};
+
+    use ratatui::{backend::TestBackend, style::Color};
+
+    use super::*;
+    use crate::keymap::KeyMap;
+
+    // Plays back a list of events, keeping the window titles it was given
+    struct ScriptedEvents(VecDeque<Option<Event>>, Vec<String>);
+
+    impl EventSource for ScriptedEvents {
+        fn next_event(&mut self, _timeout: Duration) -> io::Result<Option<Event>> {
+            Ok(self.0.pop_front().flatten())
+        }
+    }
+
+    impl WindowTitle for ScriptedEvents {
+        fn set_title(&mut self, title: &str) -> io::Result<()> {
+            self.1.push(title.to_string());
+            Ok(())
+        }
+    }
+
+    // Moves a second forward every time the event loop looks at it
+    struct SteppingClock {
+        start: Instant,
+        wall_start: i64,
+        seconds: Cell<u64>,
+    }
+
+    impl Clock for SteppingClock {
+        fn instant(&self) -> Instant {
+            let seconds = self.seconds.get();
+            self.seconds.set(seconds + 1);
+            self.start + Duration::from_secs(seconds)
+        }
+
+        fn timestamp(&self) -> i64 {
+            self.wall_start + self.seconds.get() as i64
+        }
+    }
+
+    // Runs the app one second per tick, which with one second phases
+    // finishes a phase on every tick
+    fn run_phases(app: &mut App, phases: usize) -> Vec<Pomodoros> {
+        let start = app.last_tick;
+        let wall_start = app.last_tick_at;
+        app.set_running(true);
+        (1..=phases)
+            .map(|second| {
+                let offset = Duration::from_secs(second as u64);
+                app.tick_at(start + offset, wall_start + second as i64);
+                app.timer.current_phase()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn session_stops_at_the_target() {
+        let mut app = App::new(1, 1, 1, 3);
+        app.timer.set_target(2);
+
+        // pomodoro, short, pomodoro, then nothing more happens
+        run_phases(&mut app, 6);
+
+        assert_eq!(app.current_screen, Screens::Finished);
+        assert_eq!(app.timer.pomodoros(), 2);
+        assert_eq!(app.history.len(), 3);
+        assert!(!app.timer.is_running());
+    }
+
+    #[test]
+    fn quit_prompt_returns_to_the_screen_it_was_opened_from() {
+        let mut app = App {
+            current_screen: Screens::Pomodoro,
+            ..App::default()
+        };
+        app.set_running(true);
+
+        app.check_keys(KeyCode::Char('q').into());
+        assert_eq!(app.current_screen, Screens::Quit);
+        app.check_keys(KeyCode::Char('?').into());
+        assert_eq!(app.current_screen, Screens::Quit);
+        app.check_keys(KeyCode::Esc.into());
+
+        assert_eq!(app.current_screen, Screens::Pomodoro);
+        assert!(app.timer.is_running());
+        assert!(app.is_running);
+
+        app.check_keys(KeyCode::Char('q').into());
+        app.check_keys(KeyCode::Char('q').into());
+        assert!(!app.is_running);
+    }
+
+    #[test]
+    fn esc_walks_back_through_the_screens_opened_on_top() {
+        let mut app = App {
+            current_screen: Screens::Pomodoro,
+            ..App::default()
+        };
+        app.set_running(true);
+
+        app.check_keys(KeyCode::Char('?').into());
+        app.check_keys(KeyCode::Char('q').into());
+        assert_eq!(app.screen_stack, [Screens::Pomodoro, Screens::Help]);
+
+        app.check_keys(KeyCode::Esc.into());
+        assert_eq!(app.current_screen, Screens::Help);
+        app.check_keys(KeyCode::Esc.into());
+        assert_eq!(app.current_screen, Screens::Pomodoro);
+        assert!(app.timer.is_running());
+    }
+
+    #[test]
+    fn rebound_keys_replace_the_defaults() {
+        let (config, warnings) =
+            Config::parse("[keys]\nskip = \"x\"\nstats = \"v\"\nquit = \"s\"\n", None);
+        assert!(warnings.is_empty());
+        let mut app = App {
+            current_screen: Screens::Pomodoro,
+            config,
+            ..App::default()
+        };
+
+        app.check_keys(KeyCode::Char('x').into());
+        assert_eq!(app.timer.pomodoros(), 1);
+        app.check_keys(KeyCode::Char('s').into());
+        assert_eq!(app.current_screen, Screens::Quit);
+
+        // The prompt names the new key, also when squeezed into the compact view
+        for (width, height, hint) in [(60, 20, "(s/Esc)"), (15, 5, "s/Esc")] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|f| app.draw_ui(f)).unwrap();
+            let buffer = terminal.backend().buffer();
+            let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+            assert!(text.contains(hint), "{text}");
+            assert!(!text.contains("q/Esc"), "{text}");
+        }
+
+        let (config, warnings) = Config::parse("[keys]\nskip = \"q\"\n", None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(config.keys, KeyMap::default());
+    }
+
+    #[test]
+    fn vim_preset_moves_with_hjkl_and_keeps_the_arrows() {
+        let (config, warnings) = Config::parse("[keys]\npreset = \"vim\"\nquit = \"Q\"\n", None);
+        assert!(warnings.is_empty());
+        assert_eq!(config.keys.key(Actions::TaskList), KeyCode::Char('L'));
+        let mut app = App {
+            config,
+            ..App::default()
+        };
+        app.tasks = vec![Task::new("a".to_string()), Task::new("b".to_string())];
+
+        app.check_keys(KeyCode::Char('L').into());
+        assert_eq!(app.current_screen, Screens::Tasks);
+        app.check_keys(KeyCode::Char('j').into());
+        assert_eq!(app.tasks_index, 1);
+        app.check_keys(KeyCode::Up.into());
+        assert_eq!(app.tasks_index, 0);
+
+        // Quitting moved to `Q`, so `q` is free and does nothing
+        app.check_keys(KeyCode::Char('q').into());
+        assert_eq!(app.current_screen, Screens::Tasks);
+        app.check_keys(KeyCode::Char('Q').into());
+        assert_eq!(app.current_screen, Screens::Quit);
+
+        let (config, warnings) = Config::parse("[keys]\npreset = \"emacs\"\n", None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(config.keys, KeyMap::default());
+    }
+
+    #[test]
+    fn scripted_run_times_the_pomodoro_and_draws_the_quit_prompt() {
+        let mut app = App::default();
+        let clock = SteppingClock {
+            start: app.last_tick,
+            wall_start: app.last_tick_at,
+            seconds: Cell::new(0),
+        };
+        let key = |c| Some(Event::Key(KeyCode::Char(c).into()));
+        let mut events = ScriptedEvents(
+            VecDeque::from([key(' '), None, None, key('q'), key('q')]),
+            Vec::new(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+
+        app.run(&mut terminal, &mut events, &clock).unwrap();
+
+        // A tick before and after each key press and one per idle poll,
+        // all but the first with the timer running
+        assert_eq!(app.elapsed_seconds(), 7);
+        assert!(!app.is_running);
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect();
+        assert!(rows
+            .iter()
+            .any(|row| row.contains("Do you really want to quit?")));
+        // The title follows the clock, once per change
+        assert_eq!(events.1[0], "🍅 20:00 — Pomodoro (paused)");
+        assert!(events.1.contains(&"🍅 19:58 — Pomodoro".to_string()));
+        assert!(events.1.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn pomodoros_go_to_the_task_picked_from_the_list() {
+        let mut app = App::new(1, 1, 1, 3);
+        app.check_keys(KeyCode::Char('l').into());
+        for key in [KeyCode::Char('a'), KeyCode::Char('x'), KeyCode::Enter] {
+            app.check_keys(key.into());
+        }
+        for key in [KeyCode::Char('a'), KeyCode::Char('y'), KeyCode::Enter] {
+            app.check_keys(key.into());
+        }
+        assert_eq!(app.current_screen, Screens::Tasks);
+        assert_eq!(app.task.as_deref(), Some("y"));
+
+        app.check_keys(KeyCode::Up.into());
+        app.check_keys(KeyCode::Enter.into());
+        assert_eq!(app.current_screen, Screens::Main);
+        run_phases(&mut app, 1);
+
+        let counts: Vec<usize> = app.tasks.iter().map(|task| task.pomodoros).collect();
+        assert_eq!(counts, [1, 0]);
+    }
+
+    #[test]
+    fn title_bar_counts_todays_pomodoros_against_the_goal() {
+        let mut app = App::new(1, 1, 1, 3);
+        app.config.daily_goal = 2;
+        let title_row = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+            terminal.draw(|f| app.draw_ui(f)).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            let row: String = (0..buffer.area.width)
+                .map(|x| buffer[(x, 0)].symbol().to_string())
+                .collect();
+            (row, buffer[(1, 0)].fg)
+        };
+
+        run_phases(&mut app, 1);
+        let (row, color) = title_row(&mut app);
+        assert!(row.contains("[1/2 today]"), "{row}");
+        assert_eq!(color, app.config.palette().dim);
+
+        run_phases(&mut app, 2);
+        let (row, color) = title_row(&mut app);
+        assert!(row.contains("[2/2 today]"), "{row}");
+        assert_eq!(color, app.config.palette().success);
+    }
+
+    #[test]
+    fn help_opens_over_the_screen_it_came_from_and_scrolls() {
+        let mut app = App::default();
+        let rows = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+            terminal.draw(|f| app.draw_ui(f)).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.height)
+                .map(|y| {
+                    (0..buffer.area.width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        app.check_keys(KeyCode::Char('?').into());
+        assert_eq!(app.current_screen, Screens::Help);
+        let shown = rows(&mut app);
+        assert!(shown.iter().any(|row| row.contains("Start a round")));
+        // The main screen's border still shows around the modal
+        assert!(shown.iter().any(|row| row.starts_with("│")));
+        assert!(!shown.iter().any(|row| row.contains("The cycle")));
+
+        for _ in 0..100 {
+            app.check_keys(KeyCode::Down.into());
+        }
+        let shown = rows(&mut app);
+        assert!(shown.iter().any(|row| row.contains("Each phase starts")));
+        let bottom = app.help_scroll;
+        app.check_keys(KeyCode::Up.into());
+        rows(&mut app);
+        assert_eq!(app.help_scroll, bottom - 1);
+
+        app.check_keys(KeyCode::Char('?').into());
+        assert_eq!(app.current_screen, Screens::Main);
+    }
+
+    #[test]
+    fn lengths_stop_growing_at_the_longest_the_timer_can_hold() {
+        let mut app = App::default();
+        app.config.pomodoro_minutes = usize::MAX / 60;
+        app.check_keys(KeyCode::Char('c').into());
+
+        app.check_keys(KeyCode::Char('+').into());
+        assert_eq!(app.settings_draft[0], usize::MAX / 60);
+        app.check_keys(KeyCode::Char('-').into());
+        assert_eq!(app.settings_draft[0], usize::MAX / 60 - 1);
+    }
+
+    #[test]
+    fn themes_preview_on_the_settings_screen_until_cancelled() {
+        let mut app = App::default();
+        app.check_keys(KeyCode::Char('c').into());
+        for _ in 0..10 {
+            app.check_keys(KeyCode::Down.into());
+        }
+        assert_eq!(app.settings_index, THEME_ROW);
+
+        app.check_keys(KeyCode::Right.into());
+        assert_eq!(app.config.theme, Themes::Light);
+        app.check_keys(KeyCode::Left.into());
+        app.check_keys(KeyCode::Left.into());
+        assert_eq!(app.config.theme, Themes::ALL[Themes::ALL.len() - 1]);
+
+        app.check_keys(KeyCode::Esc.into());
+        assert_eq!(app.current_screen, Screens::Main);
+        assert_eq!(app.config.theme, Themes::Dark);
+    }
+
+    #[test]
+    fn ascii_mode_draws_nothing_but_plain_ascii() {
+        let mut app = App {
+            current_screen: Screens::Pomodoro,
+            ..App::default()
+        };
+        app.config.ascii = true;
+        app.config.daily_goal = 4;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        for layout in [Layouts::Single, Layouts::Split] {
+            app.config.layout = layout;
+            terminal.draw(|f| app.draw_ui(f)).unwrap();
+            let buffer = terminal.backend().buffer();
+            for cell in &buffer.content {
+                assert!(cell.symbol().is_ascii(), "{:?}", cell.symbol());
+                assert_eq!((cell.fg, cell.bg), (Color::Reset, Color::Reset));
+            }
+        }
+    }
+
+    #[test]
+    fn status_bar_follows_the_screen() {
+        let mut app = App::default();
+        let bottom_row = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+            terminal.draw(|f| app.draw_ui(f)).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, 19)].symbol().to_string())
+                .collect::<String>()
+        };
+
+        assert!(bottom_row(&mut app).contains("Space start · s stats"));
+        app.check_keys(KeyCode::Char(' ').into());
+        assert!(bottom_row(&mut app).contains("Space pause · n skip"));
+
+        app.config.status_bar = false;
+        assert!(!bottom_row(&mut app).contains("skip"));
+    }
+
+    #[test]
+    fn buttons_light_up_under_the_mouse_and_act_on_a_click() {
+        let mut app = App {
+            current_screen: Screens::Pomodoro,
+            ..App::default()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| app.draw_ui(f)).unwrap();
+        let (quit, _) = *app.buttons.last().unwrap();
+        let mouse = |kind| MouseEvent {
+            kind,
+            column: quit.x,
+            row: quit.y,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        app.mouse_event(mouse(MouseEventKind::Moved));
+        terminal.draw(|f| app.draw_ui(f)).unwrap();
+        let accent = app.config.palette().accent;
+        assert_eq!(terminal.backend().buffer()[(quit.x, quit.y)].bg, accent);
+
+        app.mouse_event(mouse(MouseEventKind::Down(MouseButton::Left)));
+        assert_eq!(app.current_screen, Screens::Quit);
+    }
+
+    #[test]
+    fn control_commands_drive_the_timer_from_any_screen() {
+        let mut app = App::default();
+        assert!(app.run_command(ControlCommands::Skip, 0).is_err());
+
+        assert_eq!(
+            app.run_command(ControlCommands::Toggle, 0).as_deref(),
+            Ok("ok")
+        );
+        assert_eq!(app.current_screen, Screens::Pomodoro);
+        assert!(app.timer.is_running());
+
+        app.open_screen(Screens::Stats);
+        app.run_command(ControlCommands::Pause, 0).unwrap();
+        assert!(!app.timer.is_running());
+        app.run_command(ControlCommands::Skip, 0).unwrap();
+        assert_eq!(app.timer.current_phase(), Pomodoros::ShortBreak);
+
+        let status = app.run_command(ControlCommands::Status, 0).unwrap();
+        assert!(status.contains("\"phase\": \"short_break\""), "{status}");
+    }
+
+    #[test]
+    fn headless_runs_are_driven_by_the_requests_alone() {
+        let mut app = App::default();
+        let clock = SteppingClock {
+            start: app.last_tick,
+            wall_start: app.last_tick_at,
+            seconds: Cell::new(0),
+        };
+        let (requests, received) = mpsc::channel();
+        let (start, started) = Request::new(ControlCommands::Start);
+        let (quit, _) = Request::new(ControlCommands::Quit);
+        requests.send(start).unwrap();
+        requests.send(quit).unwrap();
+        app.listen(received);
+
+        app.run_headless(&clock);
+
+        assert_eq!(started.recv().unwrap(), "ok");
+        assert_eq!(app.current_screen, Screens::Pomodoro);
+        assert_eq!(app.elapsed_seconds(), 1);
+        assert!(!app.is_running);
+    }
+
+    #[test]
+    fn headless_runs_count_the_time_asleep() {
+        // Jumps the wall clock ten minutes ahead after the first tick
+        struct WakingClock(SteppingClock);
+
+        impl Clock for WakingClock {
+            fn instant(&self) -> Instant {
+                self.0.instant()
             }
+
+            fn timestamp(&self) -> i64 {
+                let slept = if self.0.seconds.get() >= 2 { 600 } else { 0 };
+                self.0.timestamp() + slept
+            }
+        }
+
+        let mut app = App::default();
+        let clock = WakingClock(SteppingClock {
+            start: app.last_tick,
+            wall_start: app.last_tick_at,
+            seconds: Cell::new(0),
+        });
+        let (requests, received) = mpsc::channel();
+        for command in [ControlCommands::Start, ControlCommands::Quit] {
+            requests.send(Request::new(command).0).unwrap();
+        }
+        app.listen(received);
+
+        app.run_headless(&clock);
+
+        assert_eq!(app.current_screen, Screens::Pomodoro);
+        assert!(app.timer.is_running());
+        assert!(app.elapsed_seconds() >= 600, "{}", app.elapsed_seconds());
+    }
+
+    #[test]
+    fn small_terminals_get_the_compact_view_or_a_size_hint() {
+        let mut app = App::default();
+        let screen = |app: &mut App, width, height| {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|f| app.draw_ui(f)).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let tiny = screen(&mut app, 15, 5);
+        assert!(tiny.contains("Pomodoro"));
+        assert!(tiny.contains("20:00"));
+        assert!(screen(&mut app, 15, 1).contains("Pomodoro 20:00"));
+        app.current_screen = Screens::Pomodoro;
+        for height in [7, 9] {
+            let compact = screen(&mut app, 60, height);
+            let lines: Vec<&str> = compact.lines().map(str::trim).collect();
+            assert!(
+                lines.windows(2).any(|pair| pair == ["Pomodoro", "20:00"]),
+                "{compact}"
+            );
+        }
+        assert!(screen(&mut app, 60, 10).contains("Pomodoro timer"));
+        app.current_screen = Screens::Main;
+
+        app.current_screen = Screens::Stats;
+        assert!(screen(&mut app, 36, 12).contains("needs 40x16"));
+        assert!(!screen(&mut app, 80, 24).contains("too small"));
+    }
+
+    #[test]
+    fn interruptions_are_counted_into_the_pomodoros_record() {
+        let mut app = App {
+            current_screen: Screens::Pomodoro,
+            ..App::new(60, 60, 60, 3)
+        };
+        for key in [
+            KeyCode::Char('i'),
+            KeyCode::Tab,
+            KeyCode::Char('q'),
+            KeyCode::Enter,
+        ] {
+            app.check_keys(key.into());
         }
+        assert_eq!(app.current_screen, Screens::Pomodoro);
+        assert_eq!(app.interruption_kind, Interruptions::Internal);
+        app.check_keys(KeyCode::Char('i').into());
+        app.check_keys(KeyCode::Esc.into());
+        assert_eq!(app.interruptions, 1);
+
+        app.skip_phase();
+        assert_eq!(app.history[0].interruptions, 1);
+        assert_eq!(app.interruptions, 0);
+    }
+
+    #[test]
+    fn tasks_past_their_estimate_are_flagged() {
+        let mut app = App::new(1, 1, 1, 3);
+        app.check_keys(KeyCode::Char('l').into());
+        for key in [KeyCode::Char('a'), KeyCode::Char('x'), KeyCode::Enter] {
+            app.check_keys(key.into());
+        }
+        for key in ['+', '+', '-', '-', '-', '+'] {
+            app.check_keys(KeyCode::Char(key).into());
+        }
+        assert_eq!(app.tasks[0].estimate, 1);
+        assert_eq!(app.tasks[0].progress(), "0/1");
+
+        app.current_screen = Screens::Main;
+        run_phases(&mut app, 3);
+        assert_eq!(app.tasks[0].progress(), "2/1");
+        assert!(app.tasks[0].is_over_estimate());
+    }
+
+    #[test]
+    fn n_and_backspace_skip_and_restart_phases() {
+        let mut app = App {
+            current_screen: Screens::Pomodoro,
+            ..App::default()
+        };
+        let start = app.last_tick;
+        app.set_running(true);
+        app.tick_at(start + Duration::from_secs(30), app.last_tick_at + 30);
+
+        app.check_keys(KeyCode::Backspace.into());
+        assert_eq!(app.timer.current_phase(), Pomodoros::Pomodoro);
+        assert_eq!(app.elapsed_seconds(), 0);
+
+        app.check_keys(KeyCode::Char('n').into());
+        assert_eq!(app.timer.current_phase(), Pomodoros::ShortBreak);
+        assert_eq!(app.timer.pomodoros(), 1);
+    }
+
+    #[test]
+    fn elapsed_time_comes_from_the_clock_not_the_tick_count() {
+        let mut app = App::new(60, 60, 60, 3);
+        let start = app.last_tick;
+        let wall_start = app.last_tick_at;
+        app.set_running(true);
+
+        // A single late tick still sees all the time that passed
+        app.tick_at(start + Duration::from_secs(42), wall_start + 42);
+        assert_eq!(app.elapsed_seconds(), 42);
+
+        app.set_running(false);
+        app.tick_at(start + Duration::from_secs(50), wall_start + 50);
+        assert_eq!(app.elapsed_seconds(), 42);
+
+        app.set_running(true);
+        app.tick_at(start + Duration::from_secs(70), wall_start + 70);
+        assert_eq!(app.timer.current_phase(), Pomodoros::ShortBreak);
+        assert_eq!(app.elapsed_seconds(), 2);
+    }
+
+    #[test]
+    fn time_asleep_counts_once_the_prompt_says_so() {
+        let mut app = App::new(25 * 60, 5 * 60, 15 * 60, 4);
+        let start = app.last_tick;
+        let wall_start = app.last_tick_at;
+        app.set_running(true);
+
+        // One monotonic second, but the wall clock moved 50 minutes
+        app.tick_at(start + Duration::from_secs(1), wall_start + 50 * 60);
+        assert_eq!(app.current_screen, Screens::Suspended);
+        assert!(!app.timer.is_running());
+        assert_eq!(app.elapsed_seconds(), 1);
+
+        // Counted, that's a pomodoro, a short break and 20 minutes into
+        // the next pomodoro
+        app.check_keys(KeyCode::Char('c').into());
+        assert_eq!(app.timer.pomodoros(), 1);
+        assert_eq!(app.timer.short_breaks(), 1);
+        assert_eq!(app.timer.current_phase(), Pomodoros::Pomodoro);
+        assert_eq!(app.elapsed_seconds(), 20 * 60);
+        assert!(app.timer.is_running());
     }
 }
@@ -0,0 +1,118 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::enums::weekdays::Weekdays;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() as i64)
+}
+
+/// Where the event loop reads the time, so tests can move it by hand.
+pub trait Clock {
+    /// Monotonic time, which stands still while the machine sleeps.
+    fn instant(&self) -> Instant;
+    /// Wall-clock seconds since the epoch, like `now`.
+    fn timestamp(&self) -> i64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn timestamp(&self) -> i64 {
+        now()
+    }
+}
+
+#[cfg(unix)]
+pub fn utc_offset(timestamp: i64) -> i64 {
+    let time = timestamp as libc::time_t;
+    // SAFETY: `tm` is plain old data and localtime_r only writes into it.
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
+    }
+    tm.tm_gmtoff as i64
+}
+
+#[cfg(not(unix))]
+pub fn utc_offset(_timestamp: i64) -> i64 {
+    0
+}
+
+/// Days since the epoch in local time, where each day begins at
+/// `day_start_hour` instead of midnight so late-night sessions count
+/// towards the day they started in.
+pub fn logical_day(timestamp: i64, day_start_hour: u8) -> i64 {
+    let local = timestamp + utc_offset(timestamp) - i64::from(day_start_hour) * 60 * 60;
+    local.div_euclid(SECONDS_PER_DAY)
+}
+
+/// Minutes since local midnight.
+pub fn minute_of_day(timestamp: i64) -> u32 {
+    ((timestamp + utc_offset(timestamp)).rem_euclid(SECONDS_PER_DAY) / 60) as u32
+}
+
+pub fn weekday(day: i64) -> Weekdays {
+    // 1970-01-01 was a Thursday
+    Weekdays::ALL[(day + 3).rem_euclid(7) as usize]
+}
+
+/// The logical day the week containing `day` started on.
+pub fn week_start(day: i64, first_day: Weekdays) -> i64 {
+    day - (weekday(day).index() - first_day.index()).rem_euclid(7)
+}
+
+/// Formats a day number as `YYYY-MM-DD`.
+pub fn date_string(day: i64) -> String {
+    let (year, month, date) = civil_from_days(day);
+    format!("{year:04}-{month:02}-{date:02}")
+}
+
+// Howard Hinnant's days_from_civil, run backwards
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let date = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, date)
+}
+
+/// The day number of a `YYYY-MM-DD` date, the reverse of `date_string`.
+pub fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let date: i64 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&date) {
+        return None;
+    }
+    Some(days_from_civil(year, month, date))
+}
+
+// Howard Hinnant's days_from_civil
+fn days_from_civil(year: i64, month: i64, date: i64) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + date - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
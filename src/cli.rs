@@ -0,0 +1,240 @@
+use std::{env, path::PathBuf, process};
+
+use crate::enums::{control_commands::ControlCommands, status_formats::StatusFormats};
+
+const USAGE: &str = "\
+Usage: pomodoro-tui [OPTIONS]
+       pomodoro-tui status [--format plain|json|waybar]
+       pomodoro-tui send start|pause|toggle|skip|reset|status|quit
+
+Options:
+  --pomodoro, --work <LENGTH>    Length of a pomodoro
+  --short-break, --short <LENGTH>
+                                 Length of a short break
+  --long-break, --long <LENGTH>  Length of a long break
+  --rounds, --cycles <N>         Take a long break after every N pomodoros
+  --target <N>                   Stop after N pomodoros (0 = keep going)
+  --profile <NAME>               Use the [profiles.NAME] section of the config file
+  --minimal                      Draw the timer as a single line
+  --ascii                        Draw with plain ASCII and no colors
+  --fresh                        Start a new session instead of the saved one
+  --daemon                       Run the timer without a screen, driven by `send`
+  --export-stats <PATH>          Write the stats as JSON to PATH and exit
+  --format <FORMAT>              How `status` prints the running timer
+  -h, --help                     Print this help
+
+LENGTH is in minutes, like `25`, or with units, like `25m` or `1h30m`.
+Options given here take precedence over the config file.";
+
+#[derive(Default)]
+pub struct Args {
+    pub pomodoro_minutes: Option<usize>,
+    pub short_break_minutes: Option<usize>,
+    pub long_break_minutes: Option<usize>,
+    pub rounds: Option<usize>,
+    pub target_pomodoros: Option<usize>,
+    pub profile: Option<String>,
+    pub minimal: bool,
+    pub ascii: bool,
+    pub fresh: bool,
+    pub daemon: bool,
+    pub export_stats: Option<PathBuf>,
+    /// Set by the `status` command: print the running timer and exit.
+    pub status: Option<StatusFormats>,
+    /// Set by the `send` command: pass it to the running app and exit.
+    pub send: Option<ControlCommands>,
+}
+
+impl Args {
+    /// Parses the process arguments, printing usage and exiting on bad
+    /// input. This runs before the terminal is touched, so exiting here
+    /// can't leave it in raw mode.
+    pub fn parse_or_exit() -> Args {
+        match Args::parse(env::args().skip(1)) {
+            Ok(args) => args,
+            Err(message) => {
+                eprintln!("pomodoro-tui: {message}\n\n{USAGE}");
+                process::exit(2);
+            }
+        }
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        let mut status = false;
+        let mut format = None;
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    process::exit(0);
+                }
+                "--pomodoro" | "--work" => {
+                    let value = take_value(&flag, inline_value, &mut args)?;
+                    parsed.pomodoro_minutes = Some(parse_length(&flag, &value)?);
+                }
+                "--short-break" | "--short" => {
+                    let value = take_value(&flag, inline_value, &mut args)?;
+                    parsed.short_break_minutes = Some(parse_length(&flag, &value)?);
+                }
+                "--long-break" | "--long" => {
+                    let value = take_value(&flag, inline_value, &mut args)?;
+                    parsed.long_break_minutes = Some(parse_length(&flag, &value)?);
+                }
+                "--rounds" | "--cycles" => {
+                    let value = take_value(&flag, inline_value, &mut args)?;
+                    parsed.rounds = Some(parse_positive(&flag, &value)?);
+                }
+                "--target" => {
+                    let value = take_value(&flag, inline_value, &mut args)?;
+                    parsed.target_pomodoros = Some(parse_whole(&flag, &value)?);
+                }
+                "--profile" => parsed.profile = Some(take_value(&flag, inline_value, &mut args)?),
+                "--minimal" => parsed.minimal = true,
+                "--ascii" => parsed.ascii = true,
+                "--fresh" => parsed.fresh = true,
+                "--daemon" => parsed.daemon = true,
+                "--export-stats" => {
+                    let path = take_value(&flag, inline_value, &mut args)?;
+                    parsed.export_stats = Some(PathBuf::from(path));
+                }
+                "status" => status = true,
+                "send" => {
+                    let value = take_value(&flag, inline_value, &mut args)?;
+                    parsed.send = Some(value.parse().map_err(|err| format!("`send`: {err}"))?);
+                }
+                "--format" => {
+                    let value = take_value(&flag, inline_value, &mut args)?;
+                    format = Some(value.parse().map_err(|err| format!("`{flag}`: {err}"))?);
+                }
+                _ => return Err(format!("unknown argument `{flag}`")),
+            }
+        }
+
+        match (status, format) {
+            (true, format) => parsed.status = Some(format.unwrap_or_default()),
+            (false, Some(_)) => return Err("`--format` goes with `status`".to_string()),
+            (false, None) => {}
+        }
+        Ok(parsed)
+    }
+}
+
+// The value of `--flag=value`, or else the argument after the flag
+fn take_value(
+    flag: &str,
+    inline_value: Option<String>,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, String> {
+    inline_value
+        .or_else(|| args.next())
+        .ok_or_else(|| format!("`{flag}` needs a value"))
+}
+
+fn parse_positive(flag: &str, value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(0) | Err(_) => Err(format!(
+            "`{flag}` needs a positive whole number, got `{value}`"
+        )),
+        Ok(number) => Ok(number),
+    }
+}
+
+// Minutes in a length like `25`, `25m`, `2h` or `1h30m`; bare numbers are
+// minutes
+fn parse_length(flag: &str, value: &str) -> Result<usize, String> {
+    let invalid = || format!("`{flag}` needs a length like `25`, `25m` or `1h30m`, got `{value}`");
+    let too_long = || format!("`{flag}` is too long, got `{value}`");
+    // The timer counts in seconds, so those have to fit as well
+    let fits = |minutes: usize| {
+        minutes
+            .checked_mul(60)
+            .map(|_| minutes)
+            .ok_or_else(too_long)
+    };
+    if let Ok(minutes) = value.parse::<usize>() {
+        return (minutes > 0)
+            .then_some(minutes)
+            .ok_or_else(invalid)
+            .and_then(fits);
+    }
+
+    let mut minutes: usize = 0;
+    let mut number = String::new();
+    let mut seen_hours = false;
+    let mut seen_minutes = false;
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' if !seen_hours && !seen_minutes && !number.is_empty() => {
+                let hours = number.parse::<usize>().map_err(|_| invalid())?;
+                minutes = hours
+                    .checked_mul(60)
+                    .and_then(|hours| minutes.checked_add(hours))
+                    .ok_or_else(too_long)?;
+                seen_hours = true;
+                number.clear();
+            }
+            'm' if !seen_minutes && !number.is_empty() => {
+                let more = number.parse::<usize>().map_err(|_| invalid())?;
+                minutes = minutes.checked_add(more).ok_or_else(too_long)?;
+                seen_minutes = true;
+                number.clear();
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    if !number.is_empty() || minutes == 0 {
+        return Err(invalid());
+    }
+    fits(minutes)
+}
+
+fn parse_whole(flag: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("`{flag}` needs a whole number, got `{value}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lengths_take_hours_and_minutes() {
+        let lengths = [
+            ("25", 25),
+            ("25m", 25),
+            ("2h", 120),
+            ("1h30m", 90),
+            ("0h5m", 5),
+        ];
+        for (value, minutes) in lengths {
+            assert_eq!(parse_length("--work", value), Ok(minutes), "{value}");
+        }
+
+        for value in ["0", "0m", "", "m", "30s", "1h30", "30m1h", "1m1m", "-5m"] {
+            assert!(parse_length("--work", value).is_err(), "{value}");
+        }
+
+        let most = usize::MAX / 60;
+        assert_eq!(parse_length("--work", &most.to_string()), Ok(most));
+        let too_long = [
+            format!("{}", most + 1),
+            format!("{}h", most + 1),
+            format!("{}h{}m", most, usize::MAX),
+            format!("{}h{}m", most / 60, most),
+        ];
+        for value in too_long {
+            let err = parse_length("--work", &value).unwrap_err();
+            assert!(err.contains("too long"), "{value}: {err}");
+        }
+    }
+}
@@ -0,0 +1,650 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use crate::{
+    enums::{
+        actions::Actions, anchors::Anchors, background_modes::BackgroundModes, layouts::Layouts,
+        pomodoros::Pomodoros, themes::Themes, weekdays::Weekdays,
+    },
+    keymap::{self, KeyMap},
+    ui::Theme,
+};
+
+pub struct Config {
+    pub profile: Option<String>,
+    pub pomodoro_minutes: usize,
+    pub short_break_minutes: usize,
+    pub long_break_minutes: usize,
+    pub long_break_interval: usize,
+    pub sound_path: Option<PathBuf>,
+    pub phase_sounds: Vec<(Pomodoros, PathBuf)>,
+    pub muted: bool,
+    pub volume: f32,
+    pub notifications_enabled: bool,
+    pub phase_messages: Vec<(Pomodoros, String)>,
+    pub ticking_enabled: bool,
+    pub repeat_alert: bool,
+    pub repeat_alert_gap_seconds: u64,
+    pub quit_prompt_position: Anchors,
+    pub quit_prompt_width: u16,
+    pub quit_prompt_height: u16,
+    pub background_timer: BackgroundModes,
+    pub unfocused_timer: BackgroundModes,
+    pub weekly_goal: usize,
+    pub daily_goal: usize,
+    pub day_start_hour: u8,
+    pub week_start_day: Weekdays,
+    pub show_dots: bool,
+    pub status_bar: bool,
+    pub window_title: bool,
+    pub focus_mode: bool,
+    pub minimal: bool,
+    pub ascii: bool,
+    pub no_color: bool,
+    pub show_streak: bool,
+    pub cooldown_minutes: usize,
+    pub focus_lock_minutes: usize,
+    pub layout: Layouts,
+    pub target_pomodoros: usize,
+    pub auto_advance: bool,
+    pub theme: Themes,
+    /// `[colors]` entries laid over the theme, checked when read.
+    pub colors: Vec<(String, String)>,
+    pub twelve_hour_clock: bool,
+    pub keys: KeyMap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            profile: None,
+            pomodoro_minutes: 20,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_interval: 3,
+            sound_path: None,
+            phase_sounds: Vec::new(),
+            muted: false,
+            volume: 1.0,
+            notifications_enabled: true,
+            phase_messages: Vec::new(),
+            ticking_enabled: false,
+            repeat_alert: false,
+            repeat_alert_gap_seconds: 0,
+            quit_prompt_position: Anchors::Center,
+            quit_prompt_width: 60,
+            quit_prompt_height: 25,
+            background_timer: BackgroundModes::KeepRunning,
+            unfocused_timer: BackgroundModes::KeepRunning,
+            weekly_goal: 0,
+            daily_goal: 0,
+            day_start_hour: 0,
+            week_start_day: Weekdays::Monday,
+            show_dots: true,
+            status_bar: true,
+            window_title: true,
+            focus_mode: false,
+            minimal: false,
+            ascii: false,
+            no_color: false,
+            show_streak: true,
+            cooldown_minutes: 0,
+            focus_lock_minutes: 0,
+            layout: Layouts::Single,
+            target_pomodoros: 0,
+            auto_advance: true,
+            theme: Themes::Dark,
+            colors: Vec::new(),
+            twelve_hour_clock: false,
+            keys: KeyMap::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file, returning the defaults when it doesn't exist.
+    /// Problems with the file are returned as warnings instead of errors so
+    /// a typo never keeps the timer from starting.
+    ///
+    /// `profile` picks a `[profiles.NAME]` section whose keys override the
+    /// top-level ones; without it the file's `default_profile` is used.
+    pub fn load(profile: Option<&str>) -> (Config, Vec<String>) {
+        let Some(path) = config_path() else {
+            return (Config::default(), Vec::new());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => Config::parse(&contents, profile),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                let mut warnings = Vec::new();
+                if let Some(profile) = profile {
+                    warnings.push(format!(
+                        "profile `{profile}` not found, there is no config file"
+                    ));
+                }
+                (Config::default(), warnings)
+            }
+            Err(err) => (
+                Config::default(),
+                vec![format!("could not read {}: {err}", path.display())],
+            ),
+        }
+    }
+
+    pub fn parse(contents: &str, profile: Option<&str>) -> (Config, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut values = parse_values(contents, &mut warnings);
+        let mut config = Config::default();
+
+        let profile = profile
+            .map(str::to_string)
+            .or_else(|| values.get("default_profile").cloned());
+        if let Some(name) = profile {
+            let prefix = format!("profiles.{name}.");
+            let overrides: Vec<(String, String)> = values
+                .iter()
+                .filter_map(|(key, value)| {
+                    let key = key.strip_prefix(&prefix)?;
+                    Some((key.to_string(), value.clone()))
+                })
+                .collect();
+
+            if overrides.is_empty() {
+                warnings.push(format!("profile `{name}` not found in the config file"));
+            } else {
+                values.extend(overrides);
+                config.profile = Some(name);
+            }
+        }
+
+        if let Some(minutes) = read_minutes(&values, "pomodoro_minutes", &mut warnings) {
+            config.pomodoro_minutes = minutes;
+        }
+        if let Some(minutes) = read_minutes(&values, "short_break_minutes", &mut warnings) {
+            config.short_break_minutes = minutes;
+        }
+        if let Some(minutes) = read_minutes(&values, "long_break_minutes", &mut warnings) {
+            config.long_break_minutes = minutes;
+        }
+        // The old name counted short breaks, one fewer than the pomodoros
+        if let Some(breaks) = read::<usize>(&values, "short_breaks_before_long", &mut warnings) {
+            warnings.push(
+                "`short_breaks_before_long` is now `long_break_interval`, the pomodoros \
+                 before each long break"
+                    .to_string(),
+            );
+            match breaks.checked_add(1) {
+                Some(interval) => config.long_break_interval = interval,
+                None => warnings
+                    .push("`short_breaks_before_long` is too large, using the default".to_string()),
+            }
+        }
+        if let Some(interval) = read_positive(&values, "long_break_interval", &mut warnings) {
+            config.long_break_interval = interval;
+        }
+        if let Some(path) = values.get("sound_path").filter(|path| !path.is_empty()) {
+            config.sound_path = Some(PathBuf::from(path));
+        }
+        for phase in [
+            Pomodoros::Pomodoro,
+            Pomodoros::ShortBreak,
+            Pomodoros::LongBreak,
+            Pomodoros::Cooldown,
+        ] {
+            let key = format!("sounds.{}", phase.key());
+            if let Some(path) = values.get(&key).filter(|path| !path.is_empty()) {
+                config.phase_sounds.push((phase, PathBuf::from(path)));
+            }
+            let key = format!("notifications.{}", phase.key());
+            if let Some(message) = values.get(&key).filter(|message| !message.is_empty()) {
+                config.phase_messages.push((phase, message.clone()));
+            }
+        }
+        if let Some(enabled) = read(&values, "notifications_enabled", &mut warnings) {
+            config.notifications_enabled = enabled;
+        }
+        if let Some(enabled) = read(&values, "ticking_enabled", &mut warnings) {
+            config.ticking_enabled = enabled;
+        }
+        if let Some(repeat) = read(&values, "repeat_alert", &mut warnings) {
+            config.repeat_alert = repeat;
+        }
+        if let Some(gap) = read(&values, "repeat_alert_gap_seconds", &mut warnings) {
+            config.repeat_alert_gap_seconds = gap;
+        }
+        if let Some(muted) = read(&values, "muted", &mut warnings) {
+            config.muted = muted;
+        }
+        match read::<f32>(&values, "volume", &mut warnings) {
+            Some(volume) if (0.0..=1.0).contains(&volume) => config.volume = volume,
+            Some(volume) => warnings.push(format!(
+                "`volume` must be between 0.0 and 1.0, got {volume}"
+            )),
+            None => {}
+        }
+        if let Some(position) = read(&values, "quit_prompt_position", &mut warnings) {
+            config.quit_prompt_position = position;
+        }
+        if let Some(width) = read_percent(&values, "quit_prompt_width", &mut warnings) {
+            config.quit_prompt_width = width;
+        }
+        if let Some(height) = read_percent(&values, "quit_prompt_height", &mut warnings) {
+            config.quit_prompt_height = height;
+        }
+        if let Some(mode) = read(&values, "background_timer", &mut warnings) {
+            config.background_timer = mode;
+        }
+        if let Some(mode) = read(&values, "unfocused_timer", &mut warnings) {
+            config.unfocused_timer = mode;
+        }
+        if let Some(goal) = read(&values, "weekly_goal", &mut warnings) {
+            config.weekly_goal = goal;
+        }
+        if let Some(goal) = read(&values, "daily_goal", &mut warnings) {
+            config.daily_goal = goal;
+        }
+        match read::<u8>(&values, "day_start_hour", &mut warnings) {
+            Some(hour @ 0..=23) => config.day_start_hour = hour,
+            Some(hour) => warnings.push(format!("`day_start_hour` must be below 24, got {hour}")),
+            None => {}
+        }
+        if let Some(day) = read(&values, "week_start_day", &mut warnings) {
+            config.week_start_day = day;
+        }
+        if let Some(show) = read(&values, "show_dots", &mut warnings) {
+            config.show_dots = show;
+        }
+        if let Some(show) = read(&values, "status_bar", &mut warnings) {
+            config.status_bar = show;
+        }
+        if let Some(show) = read(&values, "window_title", &mut warnings) {
+            config.window_title = show;
+        }
+        if let Some(focus_mode) = read(&values, "focus_mode", &mut warnings) {
+            config.focus_mode = focus_mode;
+        }
+        if let Some(minimal) = read(&values, "minimal", &mut warnings) {
+            config.minimal = minimal;
+        }
+        if let Some(ascii) = read(&values, "ascii", &mut warnings) {
+            config.ascii = ascii;
+        }
+        if let Some(no_color) = read(&values, "no_color", &mut warnings) {
+            config.no_color = no_color;
+        }
+        if let Some(show) = read(&values, "show_streak", &mut warnings) {
+            config.show_streak = show;
+        }
+        if let Some(minutes) = read(&values, "cooldown_minutes", &mut warnings) {
+            config.cooldown_minutes = minutes;
+        }
+        if let Some(minutes) = read(&values, "focus_lock_minutes", &mut warnings) {
+            config.focus_lock_minutes = minutes;
+        }
+        if let Some(layout) = read(&values, "layout", &mut warnings) {
+            config.layout = layout;
+        }
+        if let Some(target) = read(&values, "target_pomodoros", &mut warnings) {
+            config.target_pomodoros = target;
+        }
+        if let Some(auto_advance) = read(&values, "auto_advance", &mut warnings) {
+            config.auto_advance = auto_advance;
+        }
+        if let Some(theme) = read(&values, "theme", &mut warnings) {
+            config.theme = theme;
+        }
+        let mut colors: Vec<(&String, &String)> = values
+            .iter()
+            .filter(|(key, _)| key.starts_with("colors."))
+            .collect();
+        colors.sort();
+        for (key, value) in colors {
+            let name = &key["colors.".len()..];
+            match config.palette().set(name, value) {
+                Ok(()) => config.colors.push((name.to_string(), value.clone())),
+                Err(err) => warnings.push(format!("{err} in `[colors]`, using the theme's")),
+            }
+        }
+        if let Some(twelve_hour) = read(&values, "twelve_hour_clock", &mut warnings) {
+            config.twelve_hour_clock = twelve_hour;
+        }
+        // A preset is the starting point the single keys are changed from
+        let mut preset = KeyMap::default();
+        if let Some(name) = values.get("keys.preset") {
+            match KeyMap::preset(name) {
+                Some(keys) => preset = keys,
+                None => warnings.push(format!(
+                    "unknown key preset `{name}`, use `default` or `vim`"
+                )),
+            }
+        }
+        config.keys = preset.clone();
+        for action in Actions::ALL {
+            let name = format!("keys.{}", action.key());
+            let Some(value) = values.get(&name) else {
+                continue;
+            };
+            match keymap::parse_key(value) {
+                Some(key) => config.keys.bind(action, key),
+                None => warnings.push(format!(
+                    "invalid key `{value}` for `{name}`, use a single character, `space`, \
+                     `tab` or an arrow like `up`"
+                )),
+            }
+        }
+        // Checked once all keys are read, so two keys can be swapped
+        if let Some((first, second, key)) = config.keys.duplicate() {
+            warnings.push(format!(
+                "`keys.{}` and `keys.{}` are both `{}`, using the preset's keys",
+                first.key(),
+                second.key(),
+                keymap::key_name(key)
+            ));
+            config.keys = preset;
+        }
+
+        (config, warnings)
+    }
+}
+
+impl Config {
+    /// Sound files to try, in order, when `phase` ends.
+    pub fn sounds_for(&self, phase: Pomodoros) -> Vec<PathBuf> {
+        self.phase_sounds
+            .iter()
+            .filter(|(sound_phase, _)| *sound_phase == phase)
+            .map(|(_, path)| path.clone())
+            .chain(self.sound_path.clone())
+            .collect()
+    }
+
+    /// The theme with the `[colors]` entries applied.
+    pub fn palette(&self) -> Theme {
+        let mut theme = self.theme.palette();
+        for (name, value) in &self.colors {
+            // Every entry was checked when the config was read
+            let _ = theme.set(name, value);
+        }
+        theme
+    }
+
+    /// The notification text template set for the end of `phase`, if any.
+    pub fn message_for(&self, phase: Pomodoros) -> Option<&str> {
+        self.phase_messages
+            .iter()
+            .find(|(message_phase, _)| *message_phase == phase)
+            .map(|(_, message)| message.as_str())
+    }
+}
+
+/// Writes a single `key = value` pair back to the config file, leaving every
+/// other line (comments included) untouched. With a profile active it goes
+/// in that profile's table, where it would otherwise be overridden or leak
+/// into the other profiles.
+pub fn save_value(profile: Option<&str>, key: &str, value: &str) -> io::Result<()> {
+    let Some(path) = config_path() else {
+        return Ok(());
+    };
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, set_value(&contents, profile, key, value))
+}
+
+// `contents` with `key` set to `value`, at the top level or in
+// `[profiles.NAME]`, which is added at the end if there isn't one
+fn set_value(contents: &str, profile: Option<&str>, key: &str, value: &str) -> String {
+    let entry = format!("{key} = {value}");
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    let section_name = |line: &str| {
+        let line = strip_comment(line).trim();
+        let name = line.strip_prefix('[')?.strip_suffix(']')?;
+        Some(name.trim().to_string())
+    };
+    let next_section = |lines: &[String], from: usize| {
+        lines[from..]
+            .iter()
+            .position(|line| section_name(line).is_some())
+            .map_or(lines.len(), |offset| from + offset)
+    };
+
+    let (start, end) = match profile {
+        None => (0, next_section(&lines, 0)),
+        Some(profile) => {
+            let header = format!("profiles.{profile}");
+            match lines
+                .iter()
+                .position(|line| section_name(line).as_deref() == Some(header.as_str()))
+            {
+                Some(index) => (index + 1, next_section(&lines, index + 1)),
+                None => {
+                    if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                        lines.push(String::new());
+                    }
+                    lines.push(format!("[{header}]"));
+                    (lines.len(), lines.len())
+                }
+            }
+        }
+    };
+
+    let existing = lines[start..end].iter().position(|line| {
+        strip_comment(line)
+            .split_once('=')
+            .is_some_and(|(k, _)| k.trim() == key)
+    });
+    match existing {
+        Some(offset) => lines[start + offset] = entry,
+        None => {
+            // After the table's last entry, not after the blank lines that
+            // separate it from the next one
+            let at = lines[start..end]
+                .iter()
+                .rposition(|line| !line.trim().is_empty())
+                .map_or(start, |offset| start + offset + 1);
+            lines.insert(at, entry);
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("pomodoro-tui"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+// A small subset of TOML: `[section]` headers and `key = value` pairs with
+// string, integer, float or boolean values. Keys inside a section are stored
+// as `section.key`.
+fn parse_values(contents: &str, warnings: &mut Vec<String>) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut section = String::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            warnings.push(format!(
+                "config line {}: expected `key = value`",
+                number + 1
+            ));
+            continue;
+        };
+
+        let key = key.trim();
+        let key = if section.is_empty() {
+            key.to_string()
+        } else {
+            format!("{section}.{key}")
+        };
+        values.insert(key, unquote(value.trim()).to_string());
+    }
+
+    values
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn read<T: FromStr>(
+    values: &HashMap<String, String>,
+    key: &str,
+    warnings: &mut Vec<String>,
+) -> Option<T> {
+    let value = values.get(key)?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            warnings.push(format!(
+                "invalid value `{value}` for `{key}`, using the default"
+            ));
+            None
+        }
+    }
+}
+
+fn read_positive(
+    values: &HashMap<String, String>,
+    key: &str,
+    warnings: &mut Vec<String>,
+) -> Option<usize> {
+    match read::<usize>(values, key, warnings)? {
+        0 => {
+            warnings.push(format!("`{key}` must be at least 1, using the default"));
+            None
+        }
+        minutes => Some(minutes),
+    }
+}
+
+// Like `read_positive`, for minutes that must still fit once in seconds
+fn read_minutes(
+    values: &HashMap<String, String>,
+    key: &str,
+    warnings: &mut Vec<String>,
+) -> Option<usize> {
+    let minutes = read_positive(values, key, warnings)?;
+    if minutes.checked_mul(60).is_none() {
+        warnings.push(format!("`{key}` is too long, using the default"));
+        return None;
+    }
+    Some(minutes)
+}
+
+fn read_percent(
+    values: &HashMap<String, String>,
+    key: &str,
+    warnings: &mut Vec<String>,
+) -> Option<u16> {
+    match read::<u16>(values, key, warnings)? {
+        percent @ 1..=100 => Some(percent),
+        percent => {
+            warnings.push(format!("`{key}` must be between 1 and 100, got {percent}"));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+muted = false
+
+[profiles.work]
+muted = true
+pomodoro_minutes = 50
+
+[profiles.lazy]
+pomodoro_minutes = 15
+";
+
+    #[test]
+    fn saving_a_key_the_profile_sets_changes_it_there() {
+        let saved = set_value(CONFIG, Some("work"), "muted", "false");
+
+        assert!(!Config::parse(&saved, Some("work")).0.muted);
+        assert!(!Config::parse(&saved, None).0.muted);
+        assert_eq!(Config::parse(&saved, Some("work")).0.pomodoro_minutes, 50);
+    }
+
+    #[test]
+    fn saving_a_key_the_profile_lacks_stays_in_that_profile() {
+        let saved = set_value(CONFIG, Some("lazy"), "muted", "true");
+
+        assert!(Config::parse(&saved, Some("lazy")).0.muted);
+        assert!(!Config::parse(&saved, None).0.muted);
+        assert!(saved.contains("[profiles.lazy]\npomodoro_minutes = 15\nmuted = true\n"));
+
+        let saved = set_value(&saved, Some("work"), "show_dots", "false");
+        assert!(Config::parse(&saved, Some("lazy")).0.show_dots);
+        assert!(saved.contains("pomodoro_minutes = 50\nshow_dots = false\n\n[profiles.lazy]"));
+    }
+
+    #[test]
+    fn the_old_long_break_key_counts_one_more_pomodoro() {
+        let (config, warnings) = Config::parse("short_breaks_before_long = 3\n", None);
+        assert_eq!(config.long_break_interval, 4);
+        assert_eq!(warnings.len(), 1);
+
+        let (config, warnings) = Config::parse(
+            &format!("short_breaks_before_long = {}\n", usize::MAX),
+            None,
+        );
+        assert_eq!(
+            config.long_break_interval,
+            Config::default().long_break_interval
+        );
+        assert!(warnings.iter().any(|warning| warning.contains("too large")));
+    }
+
+    #[test]
+    fn saving_without_a_profile_writes_the_top_level() {
+        let saved = set_value(CONFIG, None, "volume", "0.5");
+
+        assert!(saved.starts_with("muted = false\nvolume = 0.5\n\n[profiles.work]"));
+    }
+}
@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+/// The actions whose keys can be changed in the `[keys]` table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Actions {
+    StartPause,
+    Skip,
+    Stats,
+    Reset,
+    RestartSession,
+    Mute,
+    Help,
+    Quit,
+    Settings,
+    Task,
+    TaskList,
+    ToggleClock,
+    NextTheme,
+    ToggleDots,
+    FocusMode,
+    Longer,
+    Shorter,
+    VolumeDown,
+    VolumeUp,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Actions {
+    pub const ALL: [Actions; 23] = [
+        Actions::StartPause,
+        Actions::Skip,
+        Actions::Stats,
+        Actions::Reset,
+        Actions::RestartSession,
+        Actions::Mute,
+        Actions::Help,
+        Actions::Quit,
+        Actions::Settings,
+        Actions::Task,
+        Actions::TaskList,
+        Actions::ToggleClock,
+        Actions::NextTheme,
+        Actions::ToggleDots,
+        Actions::FocusMode,
+        Actions::Longer,
+        Actions::Shorter,
+        Actions::VolumeDown,
+        Actions::VolumeUp,
+        Actions::Up,
+        Actions::Down,
+        Actions::Left,
+        Actions::Right,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Actions::StartPause => "start_pause",
+            Actions::Skip => "skip",
+            Actions::Stats => "stats",
+            Actions::Reset => "reset",
+            Actions::RestartSession => "restart_session",
+            Actions::Mute => "mute",
+            Actions::Help => "help",
+            Actions::Quit => "quit",
+            Actions::Settings => "settings",
+            Actions::Task => "task",
+            Actions::TaskList => "task_list",
+            Actions::ToggleClock => "toggle_clock",
+            Actions::NextTheme => "next_theme",
+            Actions::ToggleDots => "toggle_dots",
+            Actions::FocusMode => "focus_mode",
+            Actions::Longer => "longer",
+            Actions::Shorter => "shorter",
+            Actions::VolumeDown => "volume_down",
+            Actions::VolumeUp => "volume_up",
+            Actions::Up => "up",
+            Actions::Down => "down",
+            Actions::Left => "left",
+            Actions::Right => "right",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Actions::StartPause => "Start a round / pause and resume",
+            Actions::Skip => "Skip to the next phase",
+            Actions::Stats => "Stats",
+            Actions::Reset => "Restart the current phase",
+            Actions::RestartSession => "Restart the session",
+            Actions::Mute => "Mute or unmute the alert",
+            Actions::Help => "Show or hide this help",
+            Actions::Quit => "Quit (asks first)",
+            Actions::Settings => "Settings (main screen)",
+            Actions::Task => "Set the task (main screen) or log an interruption (pomodoro)",
+            Actions::TaskList => "Task list (main screen)",
+            Actions::ToggleClock => "Switch between remaining and elapsed time",
+            Actions::NextTheme => "Switch to the next color theme",
+            Actions::ToggleDots => "Show or hide the progress dots",
+            Actions::FocusMode => "Focus mode: hide everything but the clock",
+            Actions::Longer => "Lengthen the phase by a minute / raise a value",
+            Actions::Shorter => "Shorten the phase by a minute / lower a value",
+            Actions::VolumeDown => "Lower the volume",
+            Actions::VolumeUp => "Raise the volume",
+            Actions::Up => "Move up in lists, scroll the help",
+            Actions::Down => "Move down in lists, scroll the help",
+            Actions::Left => "Lower the selected setting",
+            Actions::Right => "Raise the selected setting",
+        }
+    }
+}
+
+impl FromStr for Actions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Actions::ALL
+            .into_iter()
+            .find(|action| action.key() == s)
+            .ok_or_else(|| format!("unknown action `{s}`"))
+    }
+}
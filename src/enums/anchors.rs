@@ -0,0 +1,33 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchors {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl FromStr for Anchors {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(Anchors::TopLeft),
+            "top" => Ok(Anchors::Top),
+            "top-right" => Ok(Anchors::TopRight),
+            "left" => Ok(Anchors::Left),
+            "center" => Ok(Anchors::Center),
+            "right" => Ok(Anchors::Right),
+            "bottom-left" => Ok(Anchors::BottomLeft),
+            "bottom" => Ok(Anchors::Bottom),
+            "bottom-right" => Ok(Anchors::BottomRight),
+            _ => Err(format!("unknown position `{s}`")),
+        }
+    }
+}
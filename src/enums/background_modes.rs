@@ -0,0 +1,19 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackgroundModes {
+    KeepRunning,
+    AutoPause,
+}
+
+impl FromStr for BackgroundModes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep-running" => Ok(BackgroundModes::KeepRunning),
+            "auto-pause" => Ok(BackgroundModes::AutoPause),
+            _ => Err(format!("unknown background mode `{s}`")),
+        }
+    }
+}
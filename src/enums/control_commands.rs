@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+/// What scripts can ask the running app to do over the control socket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlCommands {
+    Start,
+    Pause,
+    Toggle,
+    Skip,
+    Reset,
+    Status,
+    Quit,
+}
+
+impl ControlCommands {
+    pub fn key(self) -> &'static str {
+        match self {
+            ControlCommands::Start => "start",
+            ControlCommands::Pause => "pause",
+            ControlCommands::Toggle => "toggle",
+            ControlCommands::Skip => "skip",
+            ControlCommands::Reset => "reset",
+            ControlCommands::Status => "status",
+            ControlCommands::Quit => "quit",
+        }
+    }
+}
+
+impl FromStr for ControlCommands {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(ControlCommands::Start),
+            "pause" => Ok(ControlCommands::Pause),
+            "toggle" => Ok(ControlCommands::Toggle),
+            "skip" => Ok(ControlCommands::Skip),
+            "reset" => Ok(ControlCommands::Reset),
+            "status" => Ok(ControlCommands::Status),
+            "quit" => Ok(ControlCommands::Quit),
+            _ => Err(format!(
+                "unknown command `{s}`, expected start, pause, toggle, skip, reset, status or quit"
+            )),
+        }
+    }
+}
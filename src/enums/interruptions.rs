@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interruptions {
+    Internal,
+    External,
+}
+
+impl Interruptions {
+    pub fn key(self) -> &'static str {
+        match self {
+            Interruptions::Internal => "internal",
+            Interruptions::External => "external",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Interruptions::Internal => Interruptions::External,
+            Interruptions::External => Interruptions::Internal,
+        }
+    }
+}
+
+impl FromStr for Interruptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "internal" => Ok(Interruptions::Internal),
+            "external" => Ok(Interruptions::External),
+            _ => Err(format!("unknown interruption `{s}`")),
+        }
+    }
+}
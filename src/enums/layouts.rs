@@ -0,0 +1,19 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layouts {
+    Single,
+    Split,
+}
+
+impl FromStr for Layouts {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(Layouts::Single),
+            "split" => Ok(Layouts::Split),
+            _ => Err(format!("unknown layout `{s}`")),
+        }
+    }
+}
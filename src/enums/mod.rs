@@ -1,2 +1,14 @@
-pub mod pomodoros;
+pub mod actions;
+pub mod anchors;
+pub mod background_modes;
+pub mod control_commands;
+pub mod interruptions;
+pub mod layouts;
 pub mod screens;
+pub mod sizes;
+pub mod status_formats;
+pub mod themes;
+pub mod weekdays;
+
+// Shared with the library so the timer and the app agree on the type
+pub use pomodoro_tui::enums::pomodoros;
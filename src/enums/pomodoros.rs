@@ -1,5 +1,43 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Pomodoros {
     Pomodoro,
     ShortBreak,
     LongBreak,
+    Cooldown,
+}
+
+impl Pomodoros {
+    pub fn key(self) -> &'static str {
+        match self {
+            Pomodoros::Pomodoro => "pomodoro",
+            Pomodoros::ShortBreak => "short_break",
+            Pomodoros::LongBreak => "long_break",
+            Pomodoros::Cooldown => "cooldown",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Pomodoros::Pomodoro => "Pomodoro",
+            Pomodoros::ShortBreak => "Short break",
+            Pomodoros::LongBreak => "Long break",
+            Pomodoros::Cooldown => "Cooldown",
+        }
+    }
+}
+
+impl FromStr for Pomodoros {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pomodoro" => Ok(Pomodoros::Pomodoro),
+            "short_break" => Ok(Pomodoros::ShortBreak),
+            "long_break" => Ok(Pomodoros::LongBreak),
+            "cooldown" => Ok(Pomodoros::Cooldown),
+            _ => Err(format!("unknown phase `{s}`")),
+        }
+    }
 }
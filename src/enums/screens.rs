@@ -1,5 +1,46 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Screens {
     Main,
     Pomodoro,
+    Stats,
+    Settings,
+    TaskInput,
+    Interruption,
+    Tasks,
+    Help,
+    Finished,
     Quit,
+    Suspended,
+}
+
+impl Screens {
+    pub fn name(self) -> &'static str {
+        match self {
+            Screens::Main => "main screen",
+            Screens::Pomodoro => "timer",
+            Screens::Stats => "stats",
+            Screens::Settings => "settings",
+            Screens::TaskInput => "task prompt",
+            Screens::Interruption => "interruption prompt",
+            Screens::Tasks => "task list",
+            Screens::Help => "help",
+            Screens::Finished => "session summary",
+            Screens::Quit => "quit prompt",
+            Screens::Suspended => "suspend prompt",
+        }
+    }
+
+    /// Columns and rows the screen needs to be drawn without clipping.
+    pub fn min_size(self) -> (u16, u16) {
+        match self {
+            Screens::Main => (32, 14),
+            Screens::Pomodoro | Screens::TaskInput | Screens::Interruption => (30, 10),
+            Screens::Stats => (40, 16),
+            Screens::Settings => (44, 13),
+            Screens::Tasks | Screens::Help => (40, 12),
+            Screens::Finished => (34, 12),
+            Screens::Quit => (30, 10),
+            Screens::Suspended => (48, 12),
+        }
+    }
 }
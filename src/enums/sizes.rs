@@ -0,0 +1,16 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sizes {
+    Cells(u16),
+    Percent(u16),
+}
+
+impl Sizes {
+    pub fn resolve(self, available: u16) -> u16 {
+        match self {
+            Sizes::Cells(cells) => cells.min(available),
+            Sizes::Percent(percent) => {
+                (u32::from(available) * u32::from(percent.min(100)) / 100) as u16
+            }
+        }
+    }
+}
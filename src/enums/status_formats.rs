@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+/// What `pomodoro-tui status` prints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatusFormats {
+    /// One line like `🍅 14:52 ▶ 3/4`, for tmux or polybar.
+    #[default]
+    Plain,
+    Json,
+    /// The JSON a waybar custom module reads: text, tooltip and class.
+    Waybar,
+}
+
+impl FromStr for StatusFormats {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(StatusFormats::Plain),
+            "json" => Ok(StatusFormats::Json),
+            "waybar" => Ok(StatusFormats::Waybar),
+            _ => Err(format!("unknown status format `{s}`")),
+        }
+    }
+}
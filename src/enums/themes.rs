@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use ratatui::{style::Color, widgets::BorderType};
+
+use crate::ui::Theme;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Themes {
+    Dark,
+    Light,
+    Solarized,
+    HighContrast,
+    Monochrome,
+}
+
+impl Themes {
+    pub const ALL: [Themes; 5] = [
+        Themes::Dark,
+        Themes::Light,
+        Themes::Solarized,
+        Themes::HighContrast,
+        Themes::Monochrome,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Themes::Dark => "dark",
+            Themes::Light => "light",
+            Themes::Solarized => "solarized",
+            Themes::HighContrast => "high-contrast",
+            Themes::Monochrome => "monochrome",
+        }
+    }
+
+    pub fn next(self) -> Themes {
+        let index = Themes::ALL
+            .iter()
+            .position(|&theme| theme == self)
+            .unwrap_or(0);
+        Themes::ALL[(index + 1) % Themes::ALL.len()]
+    }
+
+    pub fn previous(self) -> Themes {
+        let index = Themes::ALL
+            .iter()
+            .position(|&theme| theme == self)
+            .unwrap_or(0);
+        Themes::ALL[(index + Themes::ALL.len() - 1) % Themes::ALL.len()]
+    }
+
+    pub fn palette(self) -> Theme {
+        match self {
+            Themes::Dark => Theme {
+                title: Color::Yellow,
+                accent: Color::Red,
+                dim: Color::DarkGray,
+                success: Color::Green,
+                cooldown: Color::Cyan,
+                pomodoro: Color::Red,
+                short_break: Color::Green,
+                long_break: Color::Blue,
+                gauge: Color::Red,
+                paused: Color::Reset,
+                warning: Color::Yellow,
+                highlight: Color::Yellow,
+                on_highlight: Color::Black,
+                border: BorderType::Rounded,
+            },
+            Themes::Light => Theme {
+                title: Color::Blue,
+                accent: Color::Magenta,
+                dim: Color::Gray,
+                success: Color::Green,
+                cooldown: Color::Cyan,
+                pomodoro: Color::Red,
+                short_break: Color::Green,
+                long_break: Color::Blue,
+                gauge: Color::Blue,
+                paused: Color::Magenta,
+                warning: Color::Red,
+                highlight: Color::Blue,
+                on_highlight: Color::White,
+                border: BorderType::Rounded,
+            },
+            Themes::Solarized => Theme {
+                title: Color::Rgb(0xb5, 0x89, 0x00),
+                accent: Color::Rgb(0xdc, 0x32, 0x2f),
+                dim: Color::Rgb(0x58, 0x6e, 0x75),
+                success: Color::Rgb(0x85, 0x99, 0x00),
+                cooldown: Color::Rgb(0x2a, 0xa1, 0x98),
+                pomodoro: Color::Rgb(0xdc, 0x32, 0x2f),
+                short_break: Color::Rgb(0x85, 0x99, 0x00),
+                long_break: Color::Rgb(0x26, 0x8b, 0xd2),
+                gauge: Color::Rgb(0x26, 0x8b, 0xd2),
+                paused: Color::Rgb(0xcb, 0x4b, 0x16),
+                warning: Color::Rgb(0xcb, 0x4b, 0x16),
+                highlight: Color::Rgb(0x26, 0x8b, 0xd2),
+                on_highlight: Color::Rgb(0xfd, 0xf6, 0xe3),
+                border: BorderType::Rounded,
+            },
+            Themes::HighContrast => Theme {
+                title: Color::White,
+                accent: Color::LightYellow,
+                dim: Color::Gray,
+                success: Color::LightGreen,
+                cooldown: Color::LightCyan,
+                pomodoro: Color::LightRed,
+                short_break: Color::LightGreen,
+                long_break: Color::LightCyan,
+                gauge: Color::LightYellow,
+                paused: Color::White,
+                warning: Color::LightYellow,
+                highlight: Color::White,
+                on_highlight: Color::Black,
+                border: BorderType::Thick,
+            },
+            // Only the terminal's own colors and shades of gray
+            Themes::Monochrome => Theme {
+                title: Color::Reset,
+                accent: Color::White,
+                dim: Color::DarkGray,
+                success: Color::White,
+                cooldown: Color::Gray,
+                pomodoro: Color::White,
+                short_break: Color::Gray,
+                long_break: Color::Gray,
+                gauge: Color::Gray,
+                paused: Color::Reset,
+                warning: Color::White,
+                highlight: Color::White,
+                on_highlight: Color::Black,
+                border: BorderType::Plain,
+            },
+        }
+    }
+}
+
+impl FromStr for Themes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "default" {
+            return Ok(Themes::Dark);
+        }
+        Themes::ALL
+            .into_iter()
+            .find(|theme| theme.name() == s)
+            .ok_or_else(|| format!("unknown theme `{s}`"))
+    }
+}
@@ -0,0 +1,51 @@
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weekdays {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekdays {
+    pub const ALL: [Weekdays; 7] = [
+        Weekdays::Monday,
+        Weekdays::Tuesday,
+        Weekdays::Wednesday,
+        Weekdays::Thursday,
+        Weekdays::Friday,
+        Weekdays::Saturday,
+        Weekdays::Sunday,
+    ];
+
+    pub fn index(self) -> i64 {
+        self as i64
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Weekdays::Monday => "Monday",
+            Weekdays::Tuesday => "Tuesday",
+            Weekdays::Wednesday => "Wednesday",
+            Weekdays::Thursday => "Thursday",
+            Weekdays::Friday => "Friday",
+            Weekdays::Saturday => "Saturday",
+            Weekdays::Sunday => "Sunday",
+        }
+    }
+}
+
+impl FromStr for Weekdays {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Weekdays::ALL
+            .into_iter()
+            .find(|day| day.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown weekday `{s}`"))
+    }
+}
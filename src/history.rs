@@ -0,0 +1,156 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::enums::{interruptions::Interruptions, pomodoros::Pomodoros};
+
+const HEADER: &str = "timestamp,phase,planned_seconds,interruptions,task";
+const INTERRUPTIONS_HEADER: &str = "timestamp,kind,note";
+
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub timestamp: i64,
+    pub phase: Pomodoros,
+    pub planned_seconds: usize,
+    /// Interruptions logged during the phase, only ever set for pomodoros.
+    pub interruptions: usize,
+    pub task: Option<String>,
+}
+
+impl Record {
+    fn parse(line: &str) -> Option<Record> {
+        // The task is the last column, so commas inside it can't shift the others
+        let mut fields = line.splitn(4, ',');
+        let timestamp = fields.next()?.trim().parse().ok()?;
+        let phase = fields.next()?.trim().parse().ok()?;
+        let planned_seconds = fields.next()?.trim().parse().ok()?;
+        let rest = fields.next();
+        // Files from before the interruptions column go straight to the
+        // task, which is always quoted or empty, so never a number
+        let counted = rest.and_then(|rest| {
+            let (count, task) = rest.split_once(',')?;
+            Some((count.trim().parse().ok()?, task))
+        });
+        let (interruptions, task) = match counted {
+            Some((count, task)) => (count, Some(task)),
+            None => (0, rest),
+        };
+        let record = Record {
+            timestamp,
+            phase,
+            planned_seconds,
+            interruptions,
+            task: task.map(unquote).filter(|task| !task.is_empty()),
+        };
+        Some(record)
+    }
+}
+
+/// Something that broke into a pomodoro, logged with `i` on the timer.
+#[derive(Clone, Debug)]
+pub struct Interruption {
+    pub timestamp: i64,
+    pub kind: Interruptions,
+    pub note: String,
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(base.join("pomodoro-tui"))
+}
+
+pub fn history_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("history.csv"))
+}
+
+/// Reads every completed phase from the history file. A missing file is an
+/// empty history and lines that can't be parsed are skipped.
+pub fn load() -> Vec<Record> {
+    let Some(contents) = history_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+
+    contents.lines().filter_map(Record::parse).collect()
+}
+
+pub fn interruptions_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("interruptions.csv"))
+}
+
+pub fn append(path: &Path, record: &Record) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{HEADER}")?;
+    }
+    writeln!(
+        file,
+        "{},{},{},{},{}",
+        record.timestamp,
+        record.phase.key(),
+        record.planned_seconds,
+        record.interruptions,
+        record.task.as_deref().map_or_else(String::new, quote)
+    )
+}
+
+pub fn append_interruption(path: &Path, interruption: &Interruption) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{INTERRUPTIONS_HEADER}")?;
+    }
+    writeln!(
+        file,
+        "{},{},{}",
+        interruption.timestamp,
+        interruption.kind.key(),
+        quote(&interruption.note)
+    )
+}
+
+// CSV quoting: wrap in quotes and double any quotes inside
+fn quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+fn unquote(field: &str) -> String {
+    let field = field.trim();
+    match field.strip_prefix('"').and_then(|f| f.strip_suffix('"')) {
+        Some(inner) => inner.replace("\"\"", "\""),
+        None => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_without_the_interruptions_column_still_parse() {
+        let old = Record::parse("100,pomodoro,1500,\"write, then edit\"").unwrap();
+        assert_eq!(old.interruptions, 0);
+        assert_eq!(old.task.as_deref(), Some("write, then edit"));
+
+        let new = Record::parse("100,pomodoro,1500,2,\"write, then edit\"").unwrap();
+        assert_eq!(new.interruptions, 2);
+        assert_eq!(new.task.as_deref(), Some("write, then edit"));
+
+        let untitled = Record::parse("100,short_break,300,").unwrap();
+        assert_eq!((untitled.interruptions, untitled.task), (0, None));
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+use crate::{enums::control_commands::ControlCommands, history::data_dir};
+
+/// A command read from the control socket, waiting for the event loop to
+/// carry it out and answer.
+pub struct Request {
+    pub command: ControlCommands,
+    reply: Sender<String>,
+}
+
+impl Request {
+    /// A request for `command`, with the end its answer will arrive on.
+    pub fn new(command: ControlCommands) -> (Request, Receiver<String>) {
+        let (reply, answer) = mpsc::channel();
+        (Request { command, reply }, answer)
+    }
+
+    pub fn reply(self, text: String) {
+        // The client may have hung up already, which is its business
+        let _ = self.reply.send(text);
+    }
+}
+
+/// The control socket being listened on. Dropping it removes the socket.
+pub struct Socket {
+    path: PathBuf,
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// How long a client waits for the event loop, which looks for commands
+/// every poll, so well within this.
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Hands `command` to the event loop and waits for its answer.
+pub fn ask(requests: &Sender<Request>, command: ControlCommands) -> String {
+    let (request, answer) = Request::new(command);
+    let _ = requests.send(request);
+    answer
+        .recv_timeout(REPLY_TIMEOUT)
+        .unwrap_or_else(|_| "error: the app didn't answer".to_string())
+}
+
+/// In the runtime directory when there is one, since only its owner can
+/// get in there, and next to the history otherwise.
+pub fn socket_path() -> Option<PathBuf> {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir).join("pomodoro-tui.sock")),
+        _ => Some(data_dir()?.join("control.sock")),
+    }
+}
+
+/// Starts passing commands from a socket at `path` on to `requests`, one
+/// line per connection, answered with one line: `ok`, the status as JSON,
+/// or `error: ...`. A socket left behind by an app that didn't get to clean
+/// up is replaced, but one still in use by another app is an error.
+#[cfg(unix)]
+pub fn listen(path: &Path, requests: Sender<Request>) -> io::Result<Socket> {
+    use std::{os::unix::net::UnixListener, thread};
+
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                "another pomodoro-tui is listening",
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let listener = UnixListener::bind(path)?;
+
+    thread::spawn(move || {
+        // A client that misbehaves only loses its own answer
+        for stream in listener.incoming().flatten() {
+            let _ = serve(stream, &requests);
+        }
+    });
+    Ok(Socket {
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(unix)]
+fn serve(stream: std::os::unix::net::UnixStream, requests: &Sender<Request>) -> io::Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    let reply = match line.trim().parse() {
+        Ok(command) => ask(requests, command),
+        Err(err) => format!("error: {err}"),
+    };
+    (&stream).write_all(format!("{reply}\n").as_bytes())
+}
+
+/// Sends `command` to the app listening at `path` and returns its answer.
+#[cfg(unix)]
+pub fn send(path: &Path, command: ControlCommands) -> io::Result<String> {
+    use std::{
+        io::{Read, Write},
+        net::Shutdown,
+        os::unix::net::UnixStream,
+    };
+
+    let mut stream = UnixStream::connect(path)?;
+    writeln!(stream, "{}", command.key())?;
+    stream.shutdown(Shutdown::Write)?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply.trim_end().to_string())
+}
+
+// Named pipes would do on Windows, but std has no way to make one
+#[cfg(not(unix))]
+pub fn listen(_path: &Path, _requests: Sender<Request>) -> io::Result<Socket> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the control socket needs a Unix system",
+    ))
+}
+
+#[cfg(not(unix))]
+pub fn send(_path: &Path, _command: ControlCommands) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the control socket needs a Unix system",
+    ))
+}
@@ -0,0 +1,881 @@
+//! The timer on the D-Bus session bus as `io.github.NotA0ua.PomodoroTui`,
+//! for desktop extensions and other tools. There is no D-Bus crate to lean
+//! on, so this speaks just enough of the protocol itself: it answers method
+//! calls on its own object, and sends `PhaseChanged` and the standard
+//! `PropertiesChanged` signals.
+
+use std::{
+    env,
+    io::{self, BufRead, BufReader, Read, Write},
+    os::{
+        linux::net::SocketAddrExt,
+        unix::net::{SocketAddr, UnixStream},
+    },
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        mpsc::{Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use super::control::{self, Request};
+use crate::{calendar, enums::control_commands::ControlCommands, status::Status};
+
+pub const NAME: &str = "io.github.NotA0ua.PomodoroTui";
+const PATH: &str = "/io/github/NotA0ua/PomodoroTui";
+const PROPERTIES: &str = "org.freedesktop.DBus.Properties";
+
+const METHOD_CALL: u8 = 1;
+const METHOD_RETURN: u8 = 2;
+const ERROR: u8 = 3;
+const SIGNAL: u8 = 4;
+const NO_REPLY_EXPECTED: u8 = 0x1;
+
+// The spec's upper bound on a message
+const MAX_MESSAGE_LENGTH: usize = 1 << 27;
+
+const INTROSPECTION: &str = r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node>
+  <interface name="io.github.NotA0ua.PomodoroTui">
+    <method name="Start"/>
+    <method name="Pause"/>
+    <method name="Toggle"/>
+    <method name="Skip"/>
+    <method name="Reset"/>
+    <method name="Stop"/>
+    <signal name="PhaseChanged">
+      <arg name="phase" type="s"/>
+    </signal>
+    <property name="Phase" type="s" access="read"/>
+    <property name="Running" type="b" access="read"/>
+    <property name="Remaining" type="u" access="read"/>
+    <property name="Pomodoros" type="u" access="read"/>
+    <property name="Target" type="u" access="read"/>
+    <property name="Task" type="s" access="read"/>
+  </interface>
+  <interface name="org.freedesktop.DBus.Properties">
+    <method name="Get">
+      <arg name="interface" type="s" direction="in"/>
+      <arg name="name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="GetAll">
+      <arg name="interface" type="s" direction="in"/>
+      <arg name="properties" type="a{sv}" direction="out"/>
+    </method>
+    <signal name="PropertiesChanged">
+      <arg name="interface" type="s"/>
+      <arg name="changed" type="a{sv}"/>
+      <arg name="invalidated" type="as"/>
+    </signal>
+  </interface>
+  <interface name="org.freedesktop.DBus.Introspectable">
+    <method name="Introspect">
+      <arg name="xml" type="s" direction="out"/>
+    </method>
+  </interface>
+  <interface name="org.freedesktop.DBus.Peer">
+    <method name="Ping"/>
+  </interface>
+</node>
+"#;
+
+/// Connects to the session bus and takes the name. Method calls are passed
+/// on to `requests` like commands from the control socket, and every status
+/// from `statuses` updates the properties.
+pub fn connect(requests: Sender<Request>, statuses: Receiver<Status>) -> io::Result<()> {
+    let address = env::var("DBUS_SESSION_BUS_ADDRESS")
+        .map_err(|_| io::Error::new(io::ErrorKind::NotFound, "no session bus"))?;
+    let stream = connect_address(&address)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    authenticate(&mut reader, &stream)?;
+
+    let bus = Arc::new(Bus {
+        stream: Mutex::new(stream),
+        serial: AtomicU32::new(1),
+        status: Mutex::new(None),
+    });
+    let hello = bus.call("org.freedesktop.DBus", "Hello", &[])?;
+    wait_for_reply(&mut reader, hello)?;
+    // 4 is DO_NOT_QUEUE: a second app gives up instead of waiting its turn
+    let request_name = bus.call(
+        "org.freedesktop.DBus",
+        "RequestName",
+        &[Arg::Str(NAME.to_string()), Arg::U32(4)],
+    )?;
+    let reply = wait_for_reply(&mut reader, request_name)?;
+    // 1 is PRIMARY_OWNER
+    if reply.body_reader().u32() != Some(1) {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("{NAME} is taken"),
+        ));
+    }
+
+    let incoming = Arc::clone(&bus);
+    thread::spawn(move || {
+        while let Ok(message) = Message::read(&mut reader) {
+            if message.kind == METHOD_CALL {
+                let _ = incoming.answer(&message, &requests);
+            }
+        }
+    });
+    thread::spawn(move || {
+        for status in statuses {
+            let _ = bus.update(status);
+        }
+    });
+    Ok(())
+}
+
+// The first address that can be connected to, of the `;`-separated list
+fn connect_address(addresses: &str) -> io::Result<UnixStream> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no usable bus address");
+    for address in addresses.split(';') {
+        let Some(params) = address.strip_prefix("unix:") else {
+            continue;
+        };
+        for param in params.split(',') {
+            let connected = match param.split_once('=') {
+                Some(("path", path)) => UnixStream::connect(unescape(path)),
+                Some(("abstract", name)) => SocketAddr::from_abstract_name(unescape(name))
+                    .and_then(|address| UnixStream::connect_addr(&address)),
+                _ => continue,
+            };
+            match connected {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_error = err,
+            }
+        }
+    }
+    Err(last_error)
+}
+
+// Addresses escape bytes as `%xx`
+fn unescape(value: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                bytes.push(byte);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// The EXTERNAL mechanism: the bus checks our uid on the socket itself
+fn authenticate(reader: &mut impl BufRead, mut stream: &UnixStream) -> io::Result<()> {
+    // SAFETY: getuid can't fail and touches no memory of ours.
+    let uid = unsafe { libc::getuid() }.to_string();
+    let hex: String = uid.bytes().map(|byte| format!("{byte:02x}")).collect();
+    stream.write_all(format!("\0AUTH EXTERNAL {hex}\r\n").as_bytes())?;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if !line.starts_with("OK ") {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("the bus said `{}`", line.trim_end()),
+        ));
+    }
+    stream.write_all(b"BEGIN\r\n")
+}
+
+// Reads until the answer to `serial`, which must come before anything else
+// matters as nothing has been asked of us yet
+fn wait_for_reply(reader: &mut impl Read, serial: u32) -> io::Result<Message> {
+    loop {
+        let message = Message::read(reader)?;
+        if message.reply_serial != Some(serial) {
+            continue;
+        }
+        if message.kind == ERROR {
+            let text = message.body_reader().string().unwrap_or_default();
+            return Err(io::Error::other(format!(
+                "{}: {text}",
+                message.error_name.unwrap_or_default()
+            )));
+        }
+        return Ok(message);
+    }
+}
+
+struct Bus {
+    // Writes from both threads go through here, a whole message at a time
+    stream: Mutex<UnixStream>,
+    serial: AtomicU32,
+    status: Mutex<Option<Status>>,
+}
+
+impl Bus {
+    fn send(&self, kind: u8, fields: Vec<(u8, Arg)>, body: &[Arg]) -> io::Result<u32> {
+        let serial = self.serial.fetch_add(1, Ordering::Relaxed);
+        let message = encode(kind, serial, fields, body);
+        self.stream
+            .lock()
+            .map_err(|_| io::Error::other("bus connection poisoned"))?
+            .write_all(&message)?;
+        Ok(serial)
+    }
+
+    // A call to the bus itself
+    fn call(&self, destination: &str, member: &str, body: &[Arg]) -> io::Result<u32> {
+        let fields = vec![
+            (
+                FIELD_PATH,
+                Arg::ObjectPath("/org/freedesktop/DBus".to_string()),
+            ),
+            (
+                FIELD_INTERFACE,
+                Arg::Str("org.freedesktop.DBus".to_string()),
+            ),
+            (FIELD_MEMBER, Arg::Str(member.to_string())),
+            (FIELD_DESTINATION, Arg::Str(destination.to_string())),
+        ];
+        self.send(METHOD_CALL, fields, body)
+    }
+
+    fn reply(&self, call: &Message, body: &[Arg]) -> io::Result<()> {
+        let mut fields = vec![(FIELD_REPLY_SERIAL, Arg::U32(call.serial))];
+        if let Some(sender) = &call.sender {
+            fields.push((FIELD_DESTINATION, Arg::Str(sender.clone())));
+        }
+        self.send(METHOD_RETURN, fields, body).map(drop)
+    }
+
+    fn reply_error(&self, call: &Message, name: &str, text: String) -> io::Result<()> {
+        let mut fields = vec![
+            (FIELD_ERROR_NAME, Arg::Str(name.to_string())),
+            (FIELD_REPLY_SERIAL, Arg::U32(call.serial)),
+        ];
+        if let Some(sender) = &call.sender {
+            fields.push((FIELD_DESTINATION, Arg::Str(sender.clone())));
+        }
+        self.send(ERROR, fields, &[Arg::Str(text)]).map(drop)
+    }
+
+    fn signal(&self, interface: &str, member: &str, body: &[Arg]) -> io::Result<()> {
+        let fields = vec![
+            (FIELD_PATH, Arg::ObjectPath(PATH.to_string())),
+            (FIELD_INTERFACE, Arg::Str(interface.to_string())),
+            (FIELD_MEMBER, Arg::Str(member.to_string())),
+        ];
+        self.send(SIGNAL, fields, body).map(drop)
+    }
+
+    fn answer(&self, call: &Message, requests: &Sender<Request>) -> io::Result<()> {
+        let result = self.handle(call, requests);
+        if call.flags & NO_REPLY_EXPECTED != 0 {
+            return Ok(());
+        }
+        match result {
+            Ok(body) => self.reply(call, &body),
+            Err((name, text)) => self.reply_error(call, name, text),
+        }
+    }
+
+    // The reply's body, or the error name and message
+    fn handle(
+        &self,
+        call: &Message,
+        requests: &Sender<Request>,
+    ) -> Result<Vec<Arg>, (&'static str, String)> {
+        let unknown = || {
+            (
+                "org.freedesktop.DBus.Error.UnknownMethod",
+                format!("no method `{}`", call.member.as_deref().unwrap_or_default()),
+            )
+        };
+        if call.path.as_deref() != Some(PATH) {
+            return Err((
+                "org.freedesktop.DBus.Error.UnknownObject",
+                "no such object".to_string(),
+            ));
+        }
+        let member = call.member.as_deref().unwrap_or_default();
+        match call.interface.as_deref() {
+            Some("org.freedesktop.DBus.Introspectable") if member == "Introspect" => {
+                Ok(vec![Arg::Str(INTROSPECTION.to_string())])
+            }
+            Some("org.freedesktop.DBus.Peer") if member == "Ping" => Ok(Vec::new()),
+            Some(PROPERTIES) => {
+                let mut body = call.body_reader();
+                let interface = body.string().unwrap_or_default();
+                let properties = match (interface == NAME, self.properties()) {
+                    (true, Some(properties)) => properties,
+                    (true, None) => Vec::new(),
+                    (false, _) => {
+                        return Err((
+                            "org.freedesktop.DBus.Error.UnknownInterface",
+                            format!("no interface `{interface}`"),
+                        ))
+                    }
+                };
+                match member {
+                    "GetAll" => Ok(vec![Arg::Dict(properties)]),
+                    "Get" => {
+                        let name = body.string().unwrap_or_default();
+                        match properties.into_iter().find(|(key, _)| *key == name) {
+                            Some((_, value)) => Ok(vec![Arg::Variant(Box::new(value))]),
+                            None => Err((
+                                "org.freedesktop.DBus.Error.UnknownProperty",
+                                format!("no property `{name}`"),
+                            )),
+                        }
+                    }
+                    "Set" => Err((
+                        "org.freedesktop.DBus.Error.PropertyReadOnly",
+                        "the properties are read-only".to_string(),
+                    )),
+                    _ => Err(unknown()),
+                }
+            }
+            Some(NAME) | None => {
+                // Stop works like a player's: back to the start of the phase, paused
+                let commands: &[ControlCommands] = match member {
+                    "Start" => &[ControlCommands::Start],
+                    "Pause" => &[ControlCommands::Pause],
+                    "Toggle" => &[ControlCommands::Toggle],
+                    "Skip" => &[ControlCommands::Skip],
+                    "Reset" => &[ControlCommands::Reset],
+                    "Stop" => &[ControlCommands::Pause, ControlCommands::Reset],
+                    _ => return Err(unknown()),
+                };
+                for &command in commands {
+                    let reply = control::ask(requests, command);
+                    if let Some(err) = reply.strip_prefix("error: ") {
+                        return Err((
+                            "io.github.NotA0ua.PomodoroTui.Error.Failed",
+                            err.to_string(),
+                        ));
+                    }
+                }
+                Ok(Vec::new())
+            }
+            Some(_) => Err(unknown()),
+        }
+    }
+
+    fn properties(&self) -> Option<Vec<(&'static str, Arg)>> {
+        let status = self.status.lock().ok()?;
+        Some(properties(status.as_ref()?))
+    }
+
+    // Takes in a new status and signals what changed
+    fn update(&self, status: Status) -> io::Result<()> {
+        let previous = self
+            .status
+            .lock()
+            .map_err(|_| io::Error::other("status poisoned"))?
+            .replace(status.clone());
+        if previous.as_ref().map(|previous| previous.phase) != Some(status.phase) {
+            self.signal(
+                NAME,
+                "PhaseChanged",
+                &[Arg::Str(status.phase.key().to_string())],
+            )?;
+        }
+
+        let old = previous.as_ref().map(properties).unwrap_or_default();
+        let changed: Vec<(&'static str, Arg)> = properties(&status)
+            .into_iter()
+            .filter(|property| !old.contains(property))
+            .collect();
+        if changed.is_empty() {
+            return Ok(());
+        }
+        self.signal(
+            PROPERTIES,
+            "PropertiesChanged",
+            &[
+                Arg::Str(NAME.to_string()),
+                Arg::Dict(changed),
+                Arg::Strings(Vec::new()),
+            ],
+        )
+    }
+}
+
+fn properties(status: &Status) -> Vec<(&'static str, Arg)> {
+    let count = |count: usize| Arg::U32(u32::try_from(count).unwrap_or(u32::MAX));
+    vec![
+        ("Phase", Arg::Str(status.phase.key().to_string())),
+        ("Running", Arg::Bool(status.running)),
+        ("Remaining", count(status.remaining_at(calendar::now()))),
+        ("Pomodoros", count(status.pomodoros)),
+        ("Target", count(status.target)),
+        ("Task", Arg::Str(status.task.clone().unwrap_or_default())),
+    ]
+}
+
+const FIELD_PATH: u8 = 1;
+const FIELD_INTERFACE: u8 = 2;
+const FIELD_MEMBER: u8 = 3;
+const FIELD_ERROR_NAME: u8 = 4;
+const FIELD_REPLY_SERIAL: u8 = 5;
+const FIELD_DESTINATION: u8 = 6;
+const FIELD_SENDER: u8 = 7;
+const FIELD_SIGNATURE: u8 = 8;
+
+/// The few D-Bus types the interface needs.
+#[derive(Clone, Debug, PartialEq)]
+enum Arg {
+    Bool(bool),
+    U32(u32),
+    Str(String),
+    ObjectPath(String),
+    Signature(String),
+    Variant(Box<Arg>),
+    /// `a{sv}`, the usual map of properties.
+    Dict(Vec<(&'static str, Arg)>),
+    Strings(Vec<String>),
+}
+
+impl Arg {
+    fn signature(&self) -> String {
+        match self {
+            Arg::Bool(_) => "b",
+            Arg::U32(_) => "u",
+            Arg::Str(_) => "s",
+            Arg::ObjectPath(_) => "o",
+            Arg::Signature(_) => "g",
+            Arg::Variant(_) => "v",
+            Arg::Dict(_) => "a{sv}",
+            Arg::Strings(_) => "as",
+        }
+        .to_string()
+    }
+}
+
+// Builds a whole little-endian message
+fn encode(kind: u8, serial: u32, mut fields: Vec<(u8, Arg)>, body: &[Arg]) -> Vec<u8> {
+    let mut encoded_body = Writer::default();
+    for arg in body {
+        encoded_body.arg(arg);
+    }
+    if !body.is_empty() {
+        let signature: String = body.iter().map(Arg::signature).collect();
+        fields.push((FIELD_SIGNATURE, Arg::Signature(signature)));
+    }
+
+    let mut message = Writer::default();
+    message.buf.extend_from_slice(&[b'l', kind, 0, 1]);
+    message.u32(encoded_body.buf.len() as u32);
+    message.u32(serial);
+    message.array(8, |writer| {
+        for (code, value) in &fields {
+            writer.pad(8);
+            writer.buf.push(*code);
+            writer.arg(&Arg::Variant(Box::new(value.clone())));
+        }
+    });
+    message.pad(8);
+    message.buf.extend_from_slice(&encoded_body.buf);
+    message.buf
+}
+
+// Values are aligned to their size from the start of the message; the body
+// starts on a multiple of 8, so it can be written on its own
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn pad(&mut self, alignment: usize) {
+        while !self.buf.len().is_multiple_of(alignment) {
+            self.buf.push(0);
+        }
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.pad(4);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.buf.extend_from_slice(value.as_bytes());
+        self.buf.push(0);
+    }
+
+    fn signature(&mut self, value: &str) {
+        self.buf.push(value.len() as u8);
+        self.buf.extend_from_slice(value.as_bytes());
+        self.buf.push(0);
+    }
+
+    // The length goes first but isn't known until the elements are written
+    fn array(&mut self, element_alignment: usize, elements: impl FnOnce(&mut Writer)) {
+        self.u32(0);
+        let length_at = self.buf.len() - 4;
+        self.pad(element_alignment);
+        let start = self.buf.len();
+        elements(self);
+        let length = (self.buf.len() - start) as u32;
+        self.buf[length_at..length_at + 4].copy_from_slice(&length.to_le_bytes());
+    }
+
+    fn arg(&mut self, arg: &Arg) {
+        match arg {
+            Arg::Bool(value) => self.u32(u32::from(*value)),
+            Arg::U32(value) => self.u32(*value),
+            Arg::Str(value) | Arg::ObjectPath(value) => self.string(value),
+            Arg::Signature(value) => self.signature(value),
+            Arg::Variant(value) => {
+                self.signature(&value.signature());
+                self.arg(value);
+            }
+            Arg::Dict(entries) => self.array(8, |writer| {
+                for (key, value) in entries {
+                    writer.pad(8);
+                    writer.string(key);
+                    writer.arg(&Arg::Variant(Box::new(value.clone())));
+                }
+            }),
+            Arg::Strings(values) => self.array(4, |writer| {
+                for value in values {
+                    writer.string(value);
+                }
+            }),
+        }
+    }
+}
+
+/// A message off the bus, with the header fields this module looks at.
+#[derive(Debug, Default)]
+struct Message {
+    kind: u8,
+    flags: u8,
+    serial: u32,
+    big_endian: bool,
+    path: Option<String>,
+    interface: Option<String>,
+    member: Option<String>,
+    error_name: Option<String>,
+    reply_serial: Option<u32>,
+    sender: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Message {
+    fn read(reader: &mut impl Read) -> io::Result<Message> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed D-Bus message");
+        let mut fixed = [0; 16];
+        reader.read_exact(&mut fixed)?;
+        let big_endian = match fixed[0] {
+            b'l' => false,
+            b'B' => true,
+            _ => return Err(invalid()),
+        };
+        let number = |at: usize| {
+            let bytes = [fixed[at], fixed[at + 1], fixed[at + 2], fixed[at + 3]];
+            if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            }
+        };
+        let body_length = number(4) as usize;
+        let fields_length = number(12) as usize;
+        let header_length = (16 + fields_length).next_multiple_of(8);
+        if header_length + body_length > MAX_MESSAGE_LENGTH {
+            return Err(invalid());
+        }
+
+        let mut data = fixed.to_vec();
+        data.resize(header_length + body_length, 0);
+        reader.read_exact(&mut data[16..])?;
+        let body = data.split_off(header_length);
+
+        let mut message = Message {
+            kind: fixed[1],
+            flags: fixed[2],
+            serial: number(8),
+            big_endian,
+            body,
+            ..Message::default()
+        };
+        let mut header = Reader {
+            data: &data,
+            at: 16,
+            big_endian,
+        };
+        while header.at < 16 + fields_length {
+            header.pad(8);
+            let code = header.byte().ok_or_else(invalid)?;
+            let signature = header.signature().ok_or_else(invalid)?;
+            match signature.as_str() {
+                "s" | "o" => {
+                    let value = header.string().ok_or_else(invalid)?;
+                    match code {
+                        FIELD_PATH => message.path = Some(value),
+                        FIELD_INTERFACE => message.interface = Some(value),
+                        FIELD_MEMBER => message.member = Some(value),
+                        FIELD_ERROR_NAME => message.error_name = Some(value),
+                        FIELD_SENDER => message.sender = Some(value),
+                        _ => {}
+                    }
+                }
+                "u" => {
+                    let value = header.u32().ok_or_else(invalid)?;
+                    if code == FIELD_REPLY_SERIAL {
+                        message.reply_serial = Some(value);
+                    }
+                }
+                "g" => {
+                    header.signature().ok_or_else(invalid)?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(message)
+    }
+
+    fn body_reader(&self) -> Reader<'_> {
+        Reader {
+            data: &self.body,
+            at: 0,
+            big_endian: self.big_endian,
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn pad(&mut self, alignment: usize) {
+        self.at = self.at.next_multiple_of(alignment);
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        let byte = *self.data.get(self.at)?;
+        self.at += 1;
+        Some(byte)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.pad(4);
+        let bytes: [u8; 4] = self.data.get(self.at..self.at + 4)?.try_into().ok()?;
+        self.at += 4;
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let length = self.u32()? as usize;
+        let text = self.data.get(self.at..self.at + length)?;
+        self.at += length + 1;
+        String::from_utf8(text.to_vec()).ok()
+    }
+
+    fn signature(&mut self) -> Option<String> {
+        let length = usize::from(self.byte()?);
+        let text = self.data.get(self.at..self.at + length)?;
+        self.at += length + 1;
+        String::from_utf8(text.to_vec()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::enums::pomodoros::Pomodoros;
+
+    // How `arg` comes out after `offset` bytes of something else
+    fn written(offset: usize, arg: Arg) -> Vec<u8> {
+        let mut writer = Writer {
+            buf: vec![0xff; offset],
+        };
+        writer.arg(&arg);
+        writer.buf.split_off(offset)
+    }
+
+    fn bus() -> (Bus, UnixStream) {
+        let (ours, theirs) = UnixStream::pair().unwrap();
+        let bus = Bus {
+            stream: Mutex::new(ours),
+            serial: AtomicU32::new(1),
+            status: Mutex::new(None),
+        };
+        (bus, theirs)
+    }
+
+    fn method_call(interface: &str, member: &str, body: &[Arg]) -> Message {
+        let fields = vec![
+            (FIELD_PATH, Arg::ObjectPath(PATH.to_string())),
+            (FIELD_INTERFACE, Arg::Str(interface.to_string())),
+            (FIELD_MEMBER, Arg::Str(member.to_string())),
+            (FIELD_SENDER, Arg::Str(":1.42".to_string())),
+        ];
+        let encoded = encode(METHOD_CALL, 9, fields, body);
+        Message::read(&mut encoded.as_slice()).unwrap()
+    }
+
+    fn paused_status() -> Status {
+        Status {
+            phase: Pomodoros::ShortBreak,
+            running: false,
+            remaining_seconds: 300,
+            written_at: 1_000,
+            pomodoros: 2,
+            target: 4,
+            task: None,
+        }
+    }
+
+    #[test]
+    fn each_type_is_marshalled_at_its_alignment() {
+        assert_eq!(written(1, Arg::Bool(true)), [0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(written(2, Arg::U32(0x0102_0304)), [0, 0, 4, 3, 2, 1]);
+        assert_eq!(
+            written(3, Arg::Str("ab".to_string())),
+            [0, 2, 0, 0, 0, b'a', b'b', 0]
+        );
+        assert_eq!(
+            written(0, Arg::ObjectPath("/a".to_string())),
+            [2, 0, 0, 0, b'/', b'a', 0]
+        );
+        // Signatures are a length byte and need no alignment
+        assert_eq!(
+            written(1, Arg::Signature("as".to_string())),
+            [2, b'a', b's', 0]
+        );
+        assert_eq!(
+            written(1, Arg::Variant(Box::new(Arg::U32(5)))),
+            [1, b'u', 0, 5, 0, 0, 0]
+        );
+        // The length leaves out the padding to the first 8-aligned entry
+        assert_eq!(
+            written(0, Arg::Dict(vec![("a", Arg::Bool(true))])),
+            [16, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, b'a', 0, 1, b'b', 0, 0, 0, 0, 1, 0, 0, 0]
+        );
+        assert_eq!(written(0, Arg::Dict(Vec::new())), [0; 8]);
+        assert_eq!(
+            written(0, Arg::Strings(vec!["a".to_string(), "bc".to_string()])),
+            [15, 0, 0, 0, 1, 0, 0, 0, b'a', 0, 0, 0, 2, 0, 0, 0, b'b', b'c', 0]
+        );
+    }
+
+    #[test]
+    fn introspection_lists_every_method_and_property() {
+        for method in ["Start", "Pause", "Toggle", "Skip", "Reset", "Stop"] {
+            assert!(INTROSPECTION.contains(&format!("<method name=\"{method}\"/>")));
+        }
+        for (name, value) in properties(&paused_status()) {
+            let property = format!(
+                "<property name=\"{name}\" type=\"{}\" access=\"read\"/>",
+                value.signature()
+            );
+            assert!(INTROSPECTION.contains(&property), "{property}");
+        }
+        assert_eq!(
+            INTROSPECTION.matches("<interface ").count(),
+            INTROSPECTION.matches("</interface>").count()
+        );
+    }
+
+    #[test]
+    fn method_calls_are_answered_to_their_sender() {
+        let (bus, mut theirs) = bus();
+        *bus.status.lock().unwrap() = Some(paused_status());
+        let (requests, _) = mpsc::channel();
+
+        let get = method_call(
+            PROPERTIES,
+            "Get",
+            &[
+                Arg::Str(NAME.to_string()),
+                Arg::Str("Remaining".to_string()),
+            ],
+        );
+        bus.answer(&get, &requests).unwrap();
+
+        let reply = Message::read(&mut theirs).unwrap();
+        assert_eq!((reply.kind, reply.reply_serial), (METHOD_RETURN, Some(9)));
+        let mut body = reply.body_reader();
+        assert_eq!(body.signature().as_deref(), Some("u"));
+        assert_eq!(body.u32(), Some(300));
+    }
+
+    #[test]
+    fn stop_resets_and_pauses() {
+        let (bus, mut theirs) = bus();
+        let (requests, received) = mpsc::channel::<Request>();
+        let app = thread::spawn(move || {
+            received
+                .iter()
+                .map(|request| {
+                    let command = request.command;
+                    request.reply("ok".to_string());
+                    command
+                })
+                .collect::<Vec<_>>()
+        });
+
+        bus.answer(&method_call(NAME, "Stop", &[]), &requests)
+            .unwrap();
+        drop(requests);
+
+        assert_eq!(Message::read(&mut theirs).unwrap().kind, METHOD_RETURN);
+        assert_eq!(
+            app.join().unwrap(),
+            [ControlCommands::Pause, ControlCommands::Reset]
+        );
+    }
+
+    #[test]
+    fn only_changes_are_signalled() {
+        let (bus, mut theirs) = bus();
+        bus.update(paused_status()).unwrap();
+        bus.update(paused_status()).unwrap();
+        drop(bus);
+
+        let mut members = Vec::new();
+        while let Ok(message) = Message::read(&mut theirs) {
+            members.extend(message.member);
+        }
+        assert_eq!(members, ["PhaseChanged", "PropertiesChanged"]);
+    }
+
+    #[test]
+    fn messages_read_back_what_was_encoded() {
+        let fields = vec![
+            (FIELD_PATH, Arg::ObjectPath(PATH.to_string())),
+            (FIELD_MEMBER, Arg::Str("Get".to_string())),
+            (FIELD_REPLY_SERIAL, Arg::U32(7)),
+        ];
+        let body = [Arg::Str(NAME.to_string()), Arg::Str("Phase".to_string())];
+        let encoded = encode(METHOD_CALL, 3, fields, &body);
+
+        let message = Message::read(&mut encoded.as_slice()).unwrap();
+        assert_eq!((message.kind, message.serial), (METHOD_CALL, 3));
+        assert_eq!(message.path.as_deref(), Some(PATH));
+        assert_eq!(message.member.as_deref(), Some("Get"));
+        assert_eq!(message.reply_serial, Some(7));
+        let mut reader = message.body_reader();
+        assert_eq!(reader.string().as_deref(), Some(NAME));
+        assert_eq!(reader.string().as_deref(), Some("Phase"));
+    }
+}
@@ -0,0 +1,6 @@
+//! Ways for other programs to drive the running app. Each one feeds
+//! `control::Request`s into the same channel, which the event loop reads.
+
+pub mod control;
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+pub mod dbus;
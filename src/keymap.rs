@@ -0,0 +1,135 @@
+use ratatui::crossterm::event::KeyCode;
+
+use crate::enums::actions::Actions;
+
+/// The keys bound to each configurable action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMap {
+    // Indexed by `Actions` in declaration order, the order of `Actions::ALL`
+    keys: [KeyCode; Actions::ALL.len()],
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let mut keys = KeyMap {
+            keys: [KeyCode::Null; Actions::ALL.len()],
+        };
+        let defaults = [
+            (Actions::StartPause, KeyCode::Char(' ')),
+            (Actions::Skip, KeyCode::Char('n')),
+            (Actions::Stats, KeyCode::Char('s')),
+            (Actions::Reset, KeyCode::Char('r')),
+            (Actions::RestartSession, KeyCode::Char('R')),
+            (Actions::Mute, KeyCode::Char('m')),
+            (Actions::Help, KeyCode::Char('?')),
+            (Actions::Quit, KeyCode::Char('q')),
+            (Actions::Settings, KeyCode::Char('c')),
+            (Actions::Task, KeyCode::Char('i')),
+            (Actions::TaskList, KeyCode::Char('l')),
+            (Actions::ToggleClock, KeyCode::Char('t')),
+            (Actions::NextTheme, KeyCode::Char('T')),
+            (Actions::ToggleDots, KeyCode::Char('d')),
+            (Actions::FocusMode, KeyCode::Char('f')),
+            (Actions::Longer, KeyCode::Char('+')),
+            (Actions::Shorter, KeyCode::Char('-')),
+            (Actions::VolumeDown, KeyCode::Char('<')),
+            (Actions::VolumeUp, KeyCode::Char('>')),
+            (Actions::Up, KeyCode::Up),
+            (Actions::Down, KeyCode::Down),
+            (Actions::Left, KeyCode::Left),
+            (Actions::Right, KeyCode::Right),
+        ];
+        for (action, key) in defaults {
+            keys.bind(action, key);
+        }
+        keys
+    }
+}
+
+impl KeyMap {
+    /// The defaults with h/j/k/l for moving around, which moves the task
+    /// list to `L`. The arrow keys keep working either way.
+    pub fn vim() -> Self {
+        let mut keys = KeyMap::default();
+        keys.bind(Actions::Up, KeyCode::Char('k'));
+        keys.bind(Actions::Down, KeyCode::Char('j'));
+        keys.bind(Actions::Left, KeyCode::Char('h'));
+        keys.bind(Actions::Right, KeyCode::Char('l'));
+        keys.bind(Actions::TaskList, KeyCode::Char('L'));
+        keys
+    }
+
+    /// A named set of bindings for `keys.preset`: `default` or `vim`.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(KeyMap::default()),
+            "vim" => Some(KeyMap::vim()),
+            _ => None,
+        }
+    }
+
+    pub fn key(&self, action: Actions) -> KeyCode {
+        self.keys[action as usize]
+    }
+
+    pub fn bind(&mut self, action: Actions, key: KeyCode) {
+        self.keys[action as usize] = key;
+    }
+
+    /// The action bound to a pressed key, if any.
+    pub fn action(&self, code: KeyCode) -> Option<Actions> {
+        Actions::ALL
+            .into_iter()
+            .find(|&action| self.key(action) == code)
+    }
+
+    /// The first two actions sharing a key, with the key.
+    pub fn duplicate(&self) -> Option<(Actions, Actions, KeyCode)> {
+        Actions::ALL.iter().enumerate().find_map(|(i, &first)| {
+            let key = self.key(first);
+            Actions::ALL[i + 1..]
+                .iter()
+                .find(|&&second| self.key(second) == key)
+                .map(|&second| (first, second, key))
+        })
+    }
+}
+
+/// Reads a key as written in the config: a single character, or one of
+/// `space`, `tab`, `up`, `down`, `left` and `right`.
+pub fn parse_key(value: &str) -> Option<KeyCode> {
+    let named = [
+        ("space", KeyCode::Char(' ')),
+        ("tab", KeyCode::Tab),
+        ("up", KeyCode::Up),
+        ("down", KeyCode::Down),
+        ("left", KeyCode::Left),
+        ("right", KeyCode::Right),
+    ];
+    if let Some((_, key)) = named
+        .into_iter()
+        .find(|(name, _)| value.eq_ignore_ascii_case(name))
+    {
+        return Some(key);
+    }
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(KeyCode::Char(c)),
+        _ => None,
+    }
+}
+
+/// How a key is shown in the help and on the main screen.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        // Nothing else can be bound
+        _ => "?".to_string(),
+    }
+}
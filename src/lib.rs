@@ -0,0 +1,21 @@
+//! The timer behind pomodoro-tui, without the terminal interface.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use pomodoro_tui::{enums::pomodoros::Pomodoros, PomodoroTimer};
+//!
+//! let minute = Duration::from_secs(60);
+//! let mut timer = PomodoroTimer::new(25 * minute, 5 * minute, 15 * minute, 4);
+//! timer.resume();
+//!
+//! assert_eq!(timer.tick(25 * minute), [Pomodoros::Pomodoro]);
+//! assert_eq!(timer.current_phase(), Pomodoros::ShortBreak);
+//! ```
+
+pub mod enums {
+    pub mod pomodoros;
+}
+pub mod timer;
+
+pub use timer::{PomodoroTimer, Progress};
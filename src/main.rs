@@ -1,32 +1,230 @@
-use std::{error::Error, io::stdout};
+use std::{env, error::Error, fs, io::stdout, process, sync::mpsc};
 
 use app::App;
-use ratatui::{
-    crossterm::{
-        execute,
-        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    },
-    prelude::*,
-};
+use calendar::SystemClock;
+use cli::Args;
+use config::Config;
+use ipc::control;
+use ratatui::prelude::*;
+use sound::Player;
+use tui::Crossterm;
 
 pub mod app;
+pub mod calendar;
+pub mod cli;
+pub mod config;
 pub mod enums;
+pub mod history;
+pub mod ipc;
+pub mod json;
+pub mod keymap;
+pub mod notify;
 pub mod sound;
+pub mod state;
+pub mod stats;
+pub mod status;
+pub mod tasks;
+pub mod tui;
 pub mod ui;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    enable_raw_mode()?;
-    let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+const PROFILE_VAR: &str = "POMODORO_PROFILE";
+
+// Errors are printed by hand since returning them from main shows their
+// Debug form, which for IO errors is a struct dump
+fn main() {
+    if let Err(err) = try_main() {
+        eprintln!("pomodoro-tui: {err}");
+        process::exit(1);
+    }
+}
+
+fn try_main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse_or_exit();
+
+    // --profile beats the environment, which beats the config's default_profile
+    let env_profile = env::var(PROFILE_VAR).ok().filter(|name| !name.is_empty());
+    let selected = match (args.profile, env_profile) {
+        (Some(profile), _) => Some((profile, "--profile")),
+        (None, Some(profile)) => Some((profile, PROFILE_VAR)),
+        (None, None) => None,
+    };
+
+    let (mut config, warnings) =
+        Config::load(selected.as_ref().map(|(profile, _)| profile.as_str()));
+    for warning in warnings {
+        eprintln!("pomodoro-tui: {warning}");
+    }
+    // A broken phase sound falls back to `sound_path`, and that to the default
+    let mut sound_path_works = false;
+    if let Some(path) = &config.sound_path {
+        match sound::check_file(path) {
+            Ok(()) => sound_path_works = true,
+            Err(err) => eprintln!(
+                "pomodoro-tui: can't use sound `{}`: {err}; the default sound plays instead",
+                path.display()
+            ),
+        }
+    }
+    for (phase, path) in &config.phase_sounds {
+        if let Err(err) = sound::check_file(path) {
+            let fallback = if sound_path_works {
+                "`sound_path`"
+            } else {
+                "the default sound"
+            };
+            eprintln!(
+                "pomodoro-tui: can't use the {} sound `{}`: {err}; {fallback} plays instead",
+                phase.name().to_lowercase(),
+                path.display()
+            );
+        }
+    }
+    if let Some(minutes) = args.pomodoro_minutes {
+        config.pomodoro_minutes = minutes;
+    }
+    if let Some(minutes) = args.short_break_minutes {
+        config.short_break_minutes = minutes;
+    }
+    if let Some(minutes) = args.long_break_minutes {
+        config.long_break_minutes = minutes;
+    }
+    if let Some(rounds) = args.rounds {
+        config.long_break_interval = rounds;
+    }
+    if let Some(target) = args.target_pomodoros {
+        config.target_pomodoros = target;
+    }
+    if args.minimal {
+        config.minimal = true;
+    }
+    if args.ascii {
+        config.ascii = true;
+    }
+    // https://no-color.org asks for any non-empty value to turn colors off
+    if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        config.no_color = true;
+    }
+    if env::var_os("TERM").is_some_and(|term| term == "dumb") {
+        config.ascii = true;
+    }
+
+    // Exporting never touches the terminal
+    if let Some(path) = args.export_stats {
+        let daily_totals =
+            stats::stats_path().map_or_else(Default::default, |path| stats::load(&path));
+        let summary = stats::export(&history::load(), &daily_totals, config.day_start_hour);
+        fs::write(&path, summary)
+            .map_err(|err| format!("could not write {}: {err}", path.display()))?;
+        return Ok(());
+    }
+
+    // So is printing the status of the app running elsewhere
+    if let Some(format) = args.status {
+        let status = status::status_path().and_then(|path| status::load(&path));
+        let now = calendar::now();
+        println!(
+            "{}",
+            status::render(status.as_ref(), format, now, config.ascii)
+        );
+        return Ok(());
+    }
+
+    // And sending a command to it
+    if let Some(command) = args.send {
+        let path = control::socket_path().ok_or("no runtime directory or HOME for the socket")?;
+        let reply = control::send(&path, command)
+            .map_err(|err| format!("could not reach pomodoro-tui at {}: {err}", path.display()))?;
+        return match reply.strip_prefix("error: ") {
+            Some(err) => Err(err.into()),
+            None => {
+                println!("{reply}");
+                Ok(())
+            }
+        };
+    }
+
+    if args.daemon {
+        // Nobody is there to press the key that stops a repeating alert
+        config.repeat_alert = false;
+        tui::install_signal_handlers();
+        return daemon(config, args.fresh);
+    }
 
-    let mut app = App::default();
-    app.run(&mut terminal)?;
+    let notice = match (&selected, &config.profile) {
+        (Some((_, source)), Some(active)) => {
+            Some(format!("Using profile `{active}` from {source}"))
+        }
+        _ => None,
+    };
 
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    tui::install_panic_hook();
+    tui::install_signal_handlers();
+    let mut crossterm = Crossterm;
+    let guard = tui::Guard::new(&mut crossterm)
+        .map_err(|err| format!("could not set up the terminal: {err}"))?;
 
+    // Restore the terminal before reporting an error, not after
+    let result = run(config, notice, args.fresh);
+    guard.restore()?;
+
+    result
+}
+
+fn run(config: Config, notice: Option<String>, fresh: bool) -> Result<(), Box<dyn Error>> {
+    let backend = CrosstermBackend::new(stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    let mut app = load_app(config, fresh);
+    if let Some(notice) = notice {
+        app.show_banner(notice);
+    }
+    // Scripting is a bonus, so the timer runs without it
+    let (requests, received) = mpsc::channel();
+    #[cfg(all(feature = "dbus", target_os = "linux"))]
+    if let Err(err) = ipc::dbus::connect(requests.clone(), app.watch_status()) {
+        app.show_banner(format!("Not on D-Bus: {err}"));
+    }
+    let _socket = match control::socket_path().map(|path| control::listen(&path, requests)) {
+        Some(Ok(socket)) => Some(socket),
+        Some(Err(err)) => {
+            app.show_banner(format!("No control socket: {err}"));
+            None
+        }
+        None => None,
+    };
+    app.listen(received);
+    app.run(&mut terminal, &mut Crossterm, &SystemClock)
+}
+
+// The timer without a terminal, so the control socket is all there is to
+// drive it and failing to open it is an error
+fn daemon(config: Config, fresh: bool) -> Result<(), Box<dyn Error>> {
+    let mut app = load_app(config, fresh);
+    let (requests, received) = mpsc::channel();
+    #[cfg(all(feature = "dbus", target_os = "linux"))]
+    if let Err(err) = ipc::dbus::connect(requests.clone(), app.watch_status()) {
+        eprintln!("pomodoro-tui: not on D-Bus: {err}");
+    }
+    let path = control::socket_path().ok_or("no runtime directory or HOME for the socket")?;
+    let _socket = control::listen(&path, requests)
+        .map_err(|err| format!("could not listen on {}: {err}", path.display()))?;
+    eprintln!("pomodoro-tui: listening on {}", path.display());
+    app.listen(received);
+    app.run_headless(&SystemClock);
     Ok(())
 }
+
+// The app with its history, sounds and the saved session
+fn load_app(config: Config, fresh: bool) -> App {
+    let daily_totals = stats::stats_path().map_or_else(Default::default, |path| stats::load(&path));
+    // Audio is best effort: without a device the timer stays silent
+    let player = Player::new().ok();
+    let mut app = App::from_config(config, history::load(), daily_totals, player);
+    let saved = state::state_path()
+        .filter(|_| !fresh)
+        .and_then(|path| state::load(&path));
+    if let Some(saved) = saved {
+        app.restore(saved);
+    }
+    app
+}
@@ -0,0 +1,59 @@
+use std::{
+    io,
+    process::{Command, Stdio},
+    thread,
+};
+
+/// Shows a desktop notification through the platform's notifier command.
+/// Like the sound this is best effort: a missing notifier is ignored.
+pub fn send(summary: &str, body: &str) {
+    let _ = spawn(summary, body);
+}
+
+/// Fills in a message template from the `[notifications]` table: `{phase}`
+/// is the phase that ended, `{next}` the one starting and `{task}` the task
+/// label.
+pub fn fill(template: &str, phase: &str, next: &str, task: &str) -> String {
+    template
+        .replace("{phase}", phase)
+        .replace("{next}", next)
+        .replace("{task}", task)
+}
+
+fn spawn(summary: &str, body: &str) -> io::Result<()> {
+    let mut child = notifier(summary, body)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Reap the child so finished notifiers don't linger as zombies
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn notifier(summary: &str, body: &str) -> Command {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_quote(body),
+        applescript_quote(summary)
+    );
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(script);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn notifier(summary: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command
+        .arg("--app-name=pomodoro-tui")
+        .arg(summary)
+        .arg(body);
+    command
+}
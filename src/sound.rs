@@ -1,15 +1,156 @@
-use rodio::{source::Source, Decoder, OutputStream};
+use rodio::{
+    decoder::DecoderError,
+    source::{SineWave, Source},
+    Decoder, OutputStream, PlayError, Sink, StreamError,
+};
+use std::fmt;
 use std::fs::File;
-use std::io::BufReader;
-use std::thread;
-
-pub fn play_timer_sound() {
-    thread::spawn(|| {
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-        let file = BufReader::new(File::open("sounds/timer_end_sound.mp3").unwrap());
-        let source = Decoder::new(file).unwrap();
-        stream_handle.play_raw(source.convert_samples()).unwrap();
-
-        std::thread::sleep(std::time::Duration::from_secs(5));
-    });
+use std::io::{self, BufReader, Cursor, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{config::Config, enums::pomodoros::Pomodoros};
+
+// Embedded so the binary plays its default sound from any directory
+static TIMER_SOUND: &[u8] = include_bytes!("../sounds/timer_end_sound.mp3");
+
+type BoxedSource = Box<dyn Source<Item = i16> + Send>;
+
+#[derive(Debug)]
+pub enum SoundError {
+    Device(StreamError),
+    File(io::Error),
+    Decode(DecoderError),
+    Play(PlayError),
+}
+
+impl fmt::Display for SoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoundError::Device(err) => write!(f, "no audio device: {err}"),
+            SoundError::File(err) => write!(f, "could not open the sound file: {err}"),
+            SoundError::Decode(DecoderError::UnrecognizedFormat) => {
+                write!(f, "unsupported format, use MP3, WAV, OGG Vorbis or FLAC")
+            }
+            SoundError::Decode(err) => write!(f, "could not decode the sound: {err}"),
+            SoundError::Play(err) => write!(f, "could not play sound: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SoundError {}
+
+/// The audio output, opened once at startup and kept for the lifetime of
+/// the program: dropping the stream would silence the sink.
+pub struct Player {
+    _stream: OutputStream,
+    sink: Sink,
+    // Separate so ticks never wait behind a queued alert
+    tick_sink: Sink,
+}
+
+impl Player {
+    pub fn new() -> Result<Player, SoundError> {
+        let (stream, handle) = OutputStream::try_default().map_err(SoundError::Device)?;
+        let sink = Sink::try_new(&handle).map_err(SoundError::Play)?;
+        let tick_sink = Sink::try_new(&handle).map_err(SoundError::Play)?;
+        Ok(Player {
+            _stream: stream,
+            sink,
+            tick_sink,
+        })
+    }
+
+    /// Plays the sound for the end of `ended`: the first of its configured
+    /// sounds that decodes, then the embedded sound, then a plain beep. With
+    /// `repeat` it plays until `stop_alert` is called, with
+    /// `repeat_alert_gap_seconds` of silence between plays.
+    ///
+    /// Nothing is queued while an alert is still playing, so phases ending
+    /// in quick succession can't pile up sounds behind each other; that is
+    /// the one case where this returns false.
+    pub fn play_timer_sound(&self, ended: Pomodoros, config: &Config, repeat: bool) -> bool {
+        if !self.sink.empty() {
+            return false;
+        }
+        let source = config
+            .sounds_for(ended)
+            .iter()
+            .find_map(|path| decode_file(path).ok())
+            .or_else(|| decode_embedded().ok())
+            .unwrap_or_else(beep);
+        if repeat {
+            let gap = Duration::from_secs(config.repeat_alert_gap_seconds);
+            let source = source.buffered();
+            self.sink.append(source.clone());
+            self.sink.append(source.delay(gap).repeat_infinite());
+        } else {
+            self.sink.append(source);
+        }
+        true
+    }
+
+    /// Sets the volume of everything played from now on, 1.0 being the
+    /// sound as recorded.
+    pub fn set_volume(&self, volume: f32) {
+        self.sink.set_volume(volume);
+        self.tick_sink.set_volume(volume);
+    }
+
+    pub fn stop_alert(&self) {
+        self.sink.stop();
+    }
+
+    /// Plays a short, quiet click for the focus ticking.
+    pub fn tick(&self) {
+        let click = SineWave::new(1200.0)
+            .take_duration(Duration::from_millis(15))
+            .amplify(0.08);
+        self.tick_sink.append(click);
+    }
+}
+
+/// Checks that `path` can be opened and decoded, so a bad sound file can
+/// be reported up front instead of silently falling back at the first alert.
+pub fn check_file(path: &Path) -> Result<(), SoundError> {
+    decode_file(path).map(|_| ())
+}
+
+// Decoder picks the format from the content, whatever the extension says
+fn decode_file(path: &Path) -> Result<BoxedSource, SoundError> {
+    let file = BufReader::new(File::open(path).map_err(SoundError::File)?);
+    Ok(Box::new(Decoder::new(file).map_err(SoundError::Decode)?))
+}
+
+fn decode_embedded() -> Result<BoxedSource, SoundError> {
+    let source = Decoder::new(Cursor::new(TIMER_SOUND)).map_err(SoundError::Decode)?;
+    Ok(Box::new(source))
+}
+
+// Generated, so it plays even if no sound file decodes at all
+fn beep() -> BoxedSource {
+    let tone = SineWave::new(880.0)
+        .take_duration(Duration::from_millis(400))
+        .amplify(0.3)
+        .convert_samples();
+    Box::new(tone)
+}
+
+/// Rings the terminal bell, for when there is no audio device to play on.
+pub fn bell() {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_sound_decodes() {
+        let source = decode_embedded().unwrap();
+
+        assert!(source.channels() > 0);
+        assert!(source.sample_rate() > 0);
+    }
 }
@@ -0,0 +1,125 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use pomodoro_tui::Progress;
+
+use crate::{
+    history::data_dir,
+    json::{self, Value},
+};
+
+/// The session in progress when the app last saved, to pick it up again on
+/// the next launch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SavedState {
+    pub progress: Progress,
+    pub task: Option<String>,
+}
+
+pub fn state_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("state.json"))
+}
+
+/// Reads the saved session. A missing or corrupt file is no session.
+pub fn load(path: &Path) -> Option<SavedState> {
+    let state = json::parse(&fs::read_to_string(path).ok()?)?;
+    let count = |key| state.get(key).and_then(Value::as_usize);
+    let seconds = |key| count(key).map(|seconds| Duration::from_secs(seconds as u64));
+
+    let progress = Progress {
+        phase: state.get("phase")?.as_str()?.parse().ok()?,
+        elapsed: seconds("elapsed_seconds")?,
+        pomodoros: count("pomodoros")?,
+        short_breaks: count("short_breaks")?,
+        long_breaks: count("long_breaks")?,
+        focus: seconds("focus_seconds")?,
+    };
+    let task = state
+        .get("task")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Some(SavedState { progress, task })
+}
+
+pub fn save(path: &Path, state: &SavedState) -> io::Result<()> {
+    let progress = &state.progress;
+    let task = state
+        .task
+        .as_deref()
+        .map_or("null".to_string(), json::quote);
+    let contents = format!(
+        "{{\n  \"phase\": {},\n  \"elapsed_seconds\": {},\n  \"pomodoros\": {},\n  \
+         \"short_breaks\": {},\n  \"long_breaks\": {},\n  \"focus_seconds\": {},\n  \
+         \"task\": {task}\n}}\n",
+        json::quote(progress.phase.key()),
+        progress.elapsed.as_secs(),
+        progress.pomodoros,
+        progress.short_breaks,
+        progress.long_breaks,
+        progress.focus.as_secs(),
+    );
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
+
+/// Forgets the saved session, if there is one.
+pub fn clear(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::pomodoros::Pomodoros;
+
+    #[test]
+    fn a_saved_session_loads_back_and_a_damaged_one_is_none() {
+        let dir = std::env::temp_dir().join(format!("pomodoro-tui-state-{}", std::process::id()));
+        let path = dir.join("state.json");
+        let _ = fs::remove_dir_all(&dir);
+        let state = SavedState {
+            progress: Progress {
+                phase: Pomodoros::ShortBreak,
+                elapsed: Duration::from_secs(90),
+                pomodoros: 3,
+                short_breaks: 2,
+                long_breaks: 0,
+                focus: Duration::from_secs(4500),
+            },
+            task: Some("write \"the\" report".to_string()),
+        };
+
+        assert_eq!(load(&path), None);
+        save(&path, &state).unwrap();
+        assert_eq!(load(&path), Some(state.clone()));
+
+        // Older files may have no task at all
+        let contents = fs::read_to_string(&path).unwrap();
+        let (untitled, _) = contents.split_once(",\n  \"task\"").unwrap();
+        fs::write(&path, format!("{untitled}\n}}\n")).unwrap();
+        let loaded = load(&path).unwrap();
+        assert_eq!((loaded.progress, loaded.task), (state.progress, None));
+
+        let damaged = [
+            contents.replace("  \"focus_seconds\": 4500,\n", ""),
+            contents.replace("\"pomodoros\": 3", "\"pomodoros\": \"three\""),
+            contents.replace("\"short_break\"", "\"nap\""),
+            contents.replace("90", "-90"),
+            contents[..contents.len() / 2].to_string(),
+        ];
+        for contents in damaged {
+            fs::write(&path, &contents).unwrap();
+            assert_eq!(load(&path), None, "{contents}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    calendar::{date_string, logical_day, parse_date, week_start},
+    config::config_dir,
+    enums::{pomodoros::Pomodoros, weekdays::Weekdays},
+    history::Record,
+    json::{self, Value},
+};
+
+/// Completed phases per day, keyed by `YYYY-MM-DD`.
+pub type DailyTotals = BTreeMap<String, DayTotals>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DayTotals {
+    pub pomodoros: usize,
+    pub short_breaks: usize,
+    pub long_breaks: usize,
+}
+
+impl DayTotals {
+    pub fn add(&mut self, phase: Pomodoros) {
+        match phase {
+            Pomodoros::Pomodoro => self.pomodoros += 1,
+            Pomodoros::ShortBreak => self.short_breaks += 1,
+            Pomodoros::LongBreak => self.long_breaks += 1,
+            Pomodoros::Cooldown => {}
+        }
+    }
+}
+
+pub fn stats_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("stats.json"))
+}
+
+/// Reads the daily totals. A missing or corrupt file starts fresh.
+pub fn load(path: &Path) -> DailyTotals {
+    let Some(Value::Object(days)) = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| json::parse(&contents))
+    else {
+        return DailyTotals::new();
+    };
+
+    days.into_iter()
+        .map(|(date, totals)| {
+            let count = |key| totals.get(key).and_then(Value::as_usize).unwrap_or(0);
+            let totals = DayTotals {
+                pomodoros: count("pomodoros"),
+                short_breaks: count("short_breaks"),
+                long_breaks: count("long_breaks"),
+            };
+            (date, totals)
+        })
+        .collect()
+}
+
+pub fn save(path: &Path, totals: &DailyTotals) -> io::Result<()> {
+    let days: Vec<String> = totals
+        .iter()
+        .map(|(date, day)| {
+            format!(
+                "  {}: {{\"pomodoros\": {}, \"short_breaks\": {}, \"long_breaks\": {}}}",
+                json::quote(date),
+                day.pomodoros,
+                day.short_breaks,
+                day.long_breaks
+            )
+        })
+        .collect();
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, format!("{{\n{}\n}}\n", days.join(",\n")))
+}
+
+/// A JSON summary for other tools: per-day counts from the daily totals,
+/// focus minutes from the history, and the totals of both.
+pub fn export(history: &[Record], totals: &DailyTotals, day_start_hour: u8) -> String {
+    let mut days: BTreeMap<String, (DayTotals, usize)> = totals
+        .iter()
+        .map(|(date, day)| (date.clone(), (*day, 0)))
+        .collect();
+    for record in history
+        .iter()
+        .filter(|record| record.phase == Pomodoros::Pomodoro)
+    {
+        let date = date_string(logical_day(record.timestamp, day_start_hour));
+        days.entry(date).or_default().1 += record.planned_seconds;
+    }
+
+    let entries: Vec<String> = days
+        .iter()
+        .map(|(date, (day, focus_seconds))| {
+            format!(
+                "    {{\"date\": {}, \"pomodoros\": {}, \"short_breaks\": {}, \"long_breaks\": {}, \"focus_minutes\": {}}}",
+                json::quote(date),
+                day.pomodoros,
+                day.short_breaks,
+                day.long_breaks,
+                focus_seconds / 60
+            )
+        })
+        .collect();
+    let total_pomodoros: usize = days.values().map(|(day, _)| day.pomodoros).sum();
+    let total_focus_seconds: usize = days.values().map(|(_, seconds)| seconds).sum();
+    let days = if entries.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", entries.join(",\n"))
+    };
+    format!(
+        "{{\n  \"days\": {days},\n  \"total_pomodoros\": {total_pomodoros},\n  \"total_focus_minutes\": {}\n}}\n",
+        total_focus_seconds / 60
+    )
+}
+
+/// Runs of consecutive days on which the daily goal was met, or on which
+/// any pomodoro was finished if there is no goal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Streaks {
+    /// Up to today, or up to yesterday while today's goal isn't met yet.
+    pub current: usize,
+    pub best: usize,
+}
+
+pub fn streaks(totals: &DailyTotals, today: i64, daily_goal: usize) -> Streaks {
+    let goal = daily_goal.max(1);
+    // The totals are keyed by date, so these come out in order
+    let met = totals
+        .iter()
+        .filter(|(_, day)| day.pomodoros >= goal)
+        .filter_map(|(date, _)| parse_date(date))
+        .filter(|&day| day <= today);
+
+    let mut best = 0;
+    let mut run = 0;
+    let mut last = None;
+    for day in met {
+        run = if last == Some(day - 1) { run + 1 } else { 1 };
+        best = best.max(run);
+        last = Some(day);
+    }
+    let current = match last {
+        Some(day) if day >= today - 1 => run,
+        _ => 0,
+    };
+    Streaks { current, best }
+}
+
+/// Milestones shown on the stats screen and whether each has been reached.
+pub fn achievements(totals: &DailyTotals, streaks: Streaks) -> [(&'static str, bool); 3] {
+    let pomodoros: usize = totals.values().map(|day| day.pomodoros).sum();
+    [
+        ("First pomodoro", pomodoros >= 1),
+        ("100 pomodoros", pomodoros >= 100),
+        ("7-day streak", streaks.best >= 7),
+    ]
+}
+
+pub struct WeekProgress {
+    pub completed: usize,
+    pub first_day: Weekdays,
+    pub days_left: i64,
+}
+
+pub fn pomodoros_on(history: &[Record], day: i64, day_start_hour: u8) -> usize {
+    pomodoros_between(history, day, day, day_start_hour)
+}
+
+/// Pomodoros finished on `day` for each task label, in the order the tasks
+/// were first worked on.
+pub fn task_pomodoros_on(history: &[Record], day: i64, day_start_hour: u8) -> Vec<(String, usize)> {
+    let mut tasks: Vec<(String, usize)> = Vec::new();
+    let worked = history
+        .iter()
+        .filter(|record| record.phase == Pomodoros::Pomodoro)
+        .filter(|record| logical_day(record.timestamp, day_start_hour) == day);
+    for task in worked.filter_map(|record| record.task.as_deref()) {
+        match tasks.iter_mut().find(|(name, _)| name == task) {
+            Some((_, count)) => *count += 1,
+            None => tasks.push((task.to_string(), 1)),
+        }
+    }
+    tasks
+}
+
+pub fn focus_seconds_on(history: &[Record], day: i64, day_start_hour: u8) -> usize {
+    history
+        .iter()
+        .filter(|record| record.phase == Pomodoros::Pomodoro)
+        .filter(|record| logical_day(record.timestamp, day_start_hour) == day)
+        .map(|record| record.planned_seconds)
+        .sum()
+}
+
+/// Completed pomodoros in the logical week containing `today`. Days of the
+/// week that haven't happened yet simply contribute nothing.
+pub fn week_progress(
+    history: &[Record],
+    today: i64,
+    day_start_hour: u8,
+    first_day: Weekdays,
+) -> WeekProgress {
+    let start = week_start(today, first_day);
+    WeekProgress {
+        completed: pomodoros_between(history, start, today, day_start_hour),
+        first_day,
+        days_left: start + 6 - today,
+    }
+}
+
+fn pomodoros_between(history: &[Record], from: i64, to: i64, day_start_hour: u8) -> usize {
+    history
+        .iter()
+        .filter(|record| record.phase == Pomodoros::Pomodoro)
+        .filter(|record| (from..=to).contains(&logical_day(record.timestamp, day_start_hour)))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::utc_offset;
+
+    // A pomodoro finished at `hour` local time on the calendar day `date`
+    fn pomodoro_at(date: &str, hour: i64) -> Record {
+        let local = parse_date(date).unwrap() * 24 * 60 * 60 + hour * 60 * 60;
+        Record {
+            timestamp: local - utc_offset(local),
+            phase: Pomodoros::Pomodoro,
+            planned_seconds: 1500,
+            interruptions: 0,
+            task: None,
+        }
+    }
+
+    #[test]
+    fn totals_survive_a_save_and_bad_files_start_fresh() {
+        let dir = std::env::temp_dir().join(format!("pomodoro-tui-stats-{}", std::process::id()));
+        let path = dir.join("stats.json");
+        // Left over from a run that failed halfway
+        let _ = fs::remove_dir_all(&dir);
+        let totals: DailyTotals = [("2024-03-04", (4, 3, 1)), ("2024-03-05", (1, 0, 0))]
+            .into_iter()
+            .map(|(date, (pomodoros, short_breaks, long_breaks))| {
+                let day = DayTotals {
+                    pomodoros,
+                    short_breaks,
+                    long_breaks,
+                };
+                (date.to_string(), day)
+            })
+            .collect();
+
+        assert_eq!(load(&path), DailyTotals::new());
+        save(&path, &totals).unwrap();
+        assert_eq!(load(&path), totals);
+
+        for corrupt in [
+            "{\n  \"2024-03-04\": {\"pomodoros\": 4,",
+            "[1, 2]",
+            "",
+            "pomodoros",
+        ] {
+            fs::write(&path, corrupt).unwrap();
+            assert_eq!(load(&path), DailyTotals::new(), "{corrupt}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn weeks_start_on_the_chosen_day_and_days_start_at_the_chosen_hour() {
+        let day = |date| parse_date(date).unwrap();
+        // 2024-03-03 is a Sunday, and days start at 4 in the morning
+        let history = [
+            pomodoro_at("2024-03-02", 12),
+            // Still Saturday
+            pomodoro_at("2024-03-03", 3),
+            pomodoro_at("2024-03-03", 5),
+            pomodoro_at("2024-03-03", 23),
+            // Still Sunday
+            pomodoro_at("2024-03-04", 1),
+            pomodoro_at("2024-03-05", 10),
+            Record {
+                phase: Pomodoros::ShortBreak,
+                ..pomodoro_at("2024-03-05", 11)
+            },
+        ];
+        let week = |today| week_progress(&history, day(today), 4, Weekdays::Sunday);
+
+        let tuesday = week("2024-03-05");
+        assert_eq!((tuesday.completed, tuesday.days_left), (4, 4));
+        let sunday = week("2024-03-03");
+        assert_eq!((sunday.completed, sunday.days_left), (3, 6));
+        let saturday = week("2024-03-02");
+        assert_eq!((saturday.completed, saturday.days_left), (2, 0));
+    }
+
+    #[test]
+    fn streaks_count_consecutive_days_that_met_the_goal() {
+        let day = |date| parse_date(date).unwrap();
+        let totals: DailyTotals = [
+            ("2024-02-27", 4),
+            ("2024-02-28", 4),
+            ("2024-02-29", 5),
+            ("2024-03-01", 4),
+            ("2024-03-02", 1),
+            ("2024-03-03", 4),
+            ("2024-03-04", 4),
+        ]
+        .into_iter()
+        .map(|(date, pomodoros)| {
+            let totals = DayTotals {
+                pomodoros,
+                ..DayTotals::default()
+            };
+            (date.to_string(), totals)
+        })
+        .collect();
+
+        // Today not being done yet doesn't break yesterday's streak
+        let current = streaks(&totals, day("2024-03-05"), 4);
+        assert_eq!((current.current, current.best), (2, 4));
+        assert_eq!(streaks(&totals, day("2024-03-06"), 4).current, 0);
+        assert_eq!(streaks(&totals, day("2024-03-04"), 0).best, 7);
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    enums::{pomodoros::Pomodoros, status_formats::StatusFormats},
+    history::data_dir,
+    json::{self, Value},
+    ui,
+};
+
+/// The timer as last written by a running app, for status bars to read
+/// with `pomodoro-tui status`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Status {
+    pub phase: Pomodoros,
+    pub running: bool,
+    pub remaining_seconds: usize,
+    /// When it was written; a running timer has counted down since.
+    pub written_at: i64,
+    pub pomodoros: usize,
+    /// Pomodoros the session ends after, 0 if it doesn't.
+    pub target: usize,
+    pub task: Option<String>,
+}
+
+impl Status {
+    pub fn remaining_at(&self, now: i64) -> usize {
+        if !self.running {
+            return self.remaining_seconds;
+        }
+        let passed = usize::try_from(now - self.written_at).unwrap_or(0);
+        self.remaining_seconds.saturating_sub(passed)
+    }
+}
+
+pub fn status_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("status.json"))
+}
+
+/// Reads the status. A missing or corrupt file means no app is running.
+pub fn load(path: &Path) -> Option<Status> {
+    let status = json::parse(&fs::read_to_string(path).ok()?)?;
+    let count = |key| status.get(key).and_then(Value::as_usize);
+    Some(Status {
+        phase: status.get("phase")?.as_str()?.parse().ok()?,
+        running: status.get("running") == Some(&Value::Bool(true)),
+        remaining_seconds: count("remaining_seconds")?,
+        written_at: count("written_at")? as i64,
+        pomodoros: count("pomodoros")?,
+        target: count("target").unwrap_or(0),
+        task: status
+            .get("task")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    })
+}
+
+pub fn save(path: &Path, status: &Status) -> io::Result<()> {
+    let task = status
+        .task
+        .as_deref()
+        .map_or("null".to_string(), json::quote);
+    let contents = format!(
+        "{{\"phase\": {}, \"running\": {}, \"remaining_seconds\": {}, \"written_at\": {}, \
+         \"pomodoros\": {}, \"target\": {}, \"task\": {task}}}\n",
+        json::quote(status.phase.key()),
+        status.running,
+        status.remaining_seconds,
+        status.written_at,
+        status.pomodoros,
+        status.target,
+    );
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Written whole and then moved into place, so a reader never sees half
+    let partial = path.with_extension("json.tmp");
+    fs::write(&partial, contents)?;
+    fs::rename(partial, path)
+}
+
+/// Removes the status when the app quits, so bars go blank.
+pub fn clear(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// The status as `format` at `now`; without one, an empty line (or its JSON
+/// equivalent) so a bar shows nothing while the app is closed.
+pub fn render(status: Option<&Status>, format: StatusFormats, now: i64, ascii: bool) -> String {
+    let Some(status) = status else {
+        return match format {
+            StatusFormats::Plain => String::new(),
+            StatusFormats::Json => "{\"active\": false}".to_string(),
+            StatusFormats::Waybar => "{\"text\": \"\", \"class\": \"idle\"}".to_string(),
+        };
+    };
+
+    let clock = ui::format_clock(status.remaining_at(now));
+    let count = match status.target {
+        0 => status.pomodoros.to_string(),
+        target => format!("{}/{target}", status.pomodoros),
+    };
+    let line = if ascii {
+        let state = if status.running { "" } else { " paused" };
+        format!("{} {clock}{state} {count}", status.phase.name())
+    } else {
+        let state = if status.running { "▶" } else { "⏸" };
+        format!("{} {clock} {state} {count}", ui::phase_icon(status.phase))
+    };
+
+    match format {
+        StatusFormats::Plain => line,
+        StatusFormats::Json => format!(
+            "{{\"active\": true, \"phase\": {}, \"running\": {}, \"remaining_seconds\": {}, \
+             \"pomodoros\": {}, \"target\": {}, \"task\": {}}}",
+            json::quote(status.phase.key()),
+            status.running,
+            status.remaining_at(now),
+            status.pomodoros,
+            status.target,
+            status
+                .task
+                .as_deref()
+                .map_or("null".to_string(), json::quote),
+        ),
+        StatusFormats::Waybar => {
+            let tooltip = match &status.task {
+                Some(task) => format!("{} — {task}", status.phase.name()),
+                None => status.phase.name().to_string(),
+            };
+            let class = if status.running {
+                status.phase.key()
+            } else {
+                "paused"
+            };
+            format!(
+                "{{\"text\": {}, \"tooltip\": {}, \"class\": {}, \"alt\": {}}}",
+                json::quote(&line),
+                json::quote(&tooltip),
+                json::quote(class),
+                json::quote(status.phase.key()),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_running_timer_counts_down_from_when_it_was_written() {
+        let status = Status {
+            phase: Pomodoros::Pomodoro,
+            running: true,
+            remaining_seconds: 900,
+            written_at: 1_000,
+            pomodoros: 3,
+            target: 4,
+            task: Some("report".to_string()),
+        };
+
+        let plain = render(Some(&status), StatusFormats::Plain, 1_008, false);
+        assert_eq!(plain, "🍅 14:52 ▶ 3/4");
+        let waybar = render(Some(&status), StatusFormats::Waybar, 1_008, true);
+        assert_eq!(
+            waybar,
+            "{\"text\": \"Pomodoro 14:52 3/4\", \"tooltip\": \"Pomodoro — report\", \
+             \"class\": \"pomodoro\", \"alt\": \"pomodoro\"}"
+        );
+
+        let paused = Status {
+            running: false,
+            ..status
+        };
+        assert_eq!(paused.remaining_at(5_000), 900);
+        assert_eq!(render(None, StatusFormats::Plain, 1_008, false), "");
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    history::data_dir,
+    json::{self, Value},
+};
+
+/// An entry of the task list and the pomodoros spent on it so far.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Task {
+    pub name: String,
+    pub pomodoros: usize,
+    /// Pomodoros the task was expected to take, 0 if there's no estimate.
+    pub estimate: usize,
+    pub done: bool,
+}
+
+impl Task {
+    pub fn new(name: String) -> Self {
+        Task {
+            name,
+            pomodoros: 0,
+            estimate: 0,
+            done: false,
+        }
+    }
+
+    /// Whether more pomodoros went into the task than were estimated.
+    pub fn is_over_estimate(&self) -> bool {
+        self.estimate > 0 && self.pomodoros > self.estimate
+    }
+
+    /// The pomodoros spent, against the estimate if there is one: `4/3`.
+    pub fn progress(&self) -> String {
+        match self.estimate {
+            0 => self.pomodoros.to_string(),
+            estimate => format!("{}/{estimate}", self.pomodoros),
+        }
+    }
+}
+
+pub fn tasks_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("tasks.json"))
+}
+
+/// Reads the task list. A missing or corrupt file is an empty list, and
+/// entries without a name are skipped.
+pub fn load(path: &Path) -> Vec<Task> {
+    let Some(Value::Array(tasks)) = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| json::parse(&contents))
+    else {
+        return Vec::new();
+    };
+
+    tasks
+        .iter()
+        .filter_map(|task| {
+            Some(Task {
+                name: task.get("name")?.as_str()?.to_string(),
+                pomodoros: task.get("pomodoros").and_then(Value::as_usize).unwrap_or(0),
+                estimate: task.get("estimate").and_then(Value::as_usize).unwrap_or(0),
+                done: task.get("done") == Some(&Value::Bool(true)),
+            })
+        })
+        .collect()
+}
+
+pub fn save(path: &Path, tasks: &[Task]) -> io::Result<()> {
+    let entries: Vec<String> = tasks
+        .iter()
+        .map(|task| {
+            format!(
+                "  {{\"name\": {}, \"pomodoros\": {}, \"estimate\": {}, \"done\": {}}}",
+                json::quote(&task.name),
+                task.pomodoros,
+                task.estimate,
+                task.done
+            )
+        })
+        .collect();
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, format!("[\n{}\n]\n", entries.join(",\n")))
+}
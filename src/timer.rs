@@ -0,0 +1,524 @@
+use std::time::Duration;
+
+use crate::enums::pomodoros::Pomodoros;
+
+/// Where a timer is in its session, to save it and pick it up again later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub phase: Pomodoros,
+    /// Time spent in `phase` so far.
+    pub elapsed: Duration,
+    pub pomodoros: usize,
+    pub short_breaks: usize,
+    pub long_breaks: usize,
+    /// Focus time of the pomodoros finished so far.
+    pub focus: Duration,
+}
+
+/// The pomodoro cycle on its own: the current phase, the phase lengths and
+/// the session counters. It never reads the clock; the caller says how much
+/// time passed with `tick`, so it can be driven by any event loop or test.
+#[derive(Clone, Debug)]
+pub struct PomodoroTimer {
+    phase: Pomodoros,
+    pomodoro: Duration,
+    short_break: Duration,
+    long_break: Duration,
+    cooldown: Duration,
+    long_break_interval: usize,
+    target: usize,
+    auto_advance: bool,
+    running: bool,
+    elapsed: Duration,
+    pomodoros: usize,
+    short_breaks: usize,
+    long_breaks: usize,
+    // Focus time of the pomodoros finished so far
+    focus: Duration,
+}
+
+impl PomodoroTimer {
+    /// A paused timer at the start of the first pomodoro, with no cooldown
+    /// and no target, that moves from phase to phase on its own.
+    pub fn new(
+        pomodoro: Duration,
+        short_break: Duration,
+        long_break: Duration,
+        long_break_interval: usize,
+    ) -> Self {
+        PomodoroTimer {
+            phase: Pomodoros::Pomodoro,
+            pomodoro,
+            short_break,
+            long_break,
+            cooldown: Duration::ZERO,
+            long_break_interval,
+            target: 0,
+            auto_advance: true,
+            running: false,
+            elapsed: Duration::ZERO,
+            pomodoros: 0,
+            short_breaks: 0,
+            long_breaks: 0,
+            focus: Duration::ZERO,
+        }
+    }
+
+    /// Moves the timer `dt` forward if it's running and returns the phases
+    /// that ended, in order. Time past the end of a phase carries into the
+    /// next one, so a single large `dt` can span several phases.
+    pub fn tick(&mut self, dt: Duration) -> Vec<Pomodoros> {
+        let mut ended = Vec::new();
+        if !self.running {
+            return ended;
+        }
+        self.elapsed += dt;
+        loop {
+            let duration = self.duration(self.phase);
+            if self.elapsed < duration {
+                break;
+            }
+            let overshoot = self.elapsed - duration;
+            ended.push(self.phase);
+            self.complete(duration);
+            if !self.running {
+                break;
+            }
+            if !self.auto_advance {
+                self.running = false;
+                self.elapsed = Duration::ZERO;
+                break;
+            }
+            self.elapsed = overshoot;
+            // A zero length phase ends as soon as it starts; stopping after
+            // it keeps a cycle of them from looping forever
+            if duration.is_zero() {
+                break;
+            }
+        }
+        ended
+    }
+
+    pub fn pause(&mut self) {
+        self.running = false;
+    }
+
+    pub fn resume(&mut self) {
+        self.running = true;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Ends the current phase early and returns it. A skipped pomodoro
+    /// still counts, but only for the time actually spent on it.
+    pub fn skip(&mut self) -> Pomodoros {
+        let ended = self.phase;
+        self.complete(self.elapsed.min(self.duration(ended)));
+        self.elapsed = Duration::ZERO;
+        ended
+    }
+
+    /// Starts the current phase over, keeping it paused or running.
+    pub fn restart_phase(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Switches to `phase` from its start, without counting the current one.
+    pub fn set_phase(&mut self, phase: Pomodoros) {
+        self.phase = phase;
+        self.restart_phase();
+    }
+
+    /// Clears the counters and goes back to the start of the first pomodoro.
+    pub fn reset(&mut self) {
+        self.pomodoros = 0;
+        self.short_breaks = 0;
+        self.long_breaks = 0;
+        self.focus = Duration::ZERO;
+        self.set_phase(Pomodoros::Pomodoro);
+    }
+
+    pub fn current_phase(&self) -> Pomodoros {
+        self.phase
+    }
+
+    /// Time spent running in the current phase.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration(self.phase).saturating_sub(self.elapsed)
+    }
+
+    /// How much of the current phase is done, from 0.0 to 1.0. A zero
+    /// length phase counts as done.
+    pub fn fraction_done(&self) -> f64 {
+        let duration = self.duration(self.phase);
+        if duration.is_zero() {
+            1.0
+        } else {
+            (self.elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.0)
+        }
+    }
+
+    pub fn duration(&self, phase: Pomodoros) -> Duration {
+        match phase {
+            Pomodoros::Pomodoro => self.pomodoro,
+            Pomodoros::ShortBreak => self.short_break,
+            Pomodoros::LongBreak => self.long_break,
+            Pomodoros::Cooldown => self.cooldown,
+        }
+    }
+
+    /// Changes the length of `phase`. A zero cooldown leaves it out of the
+    /// cycle. Shortening the running phase below its elapsed time ends it on
+    /// the next tick.
+    pub fn set_duration(&mut self, phase: Pomodoros, duration: Duration) {
+        let field = match phase {
+            Pomodoros::Pomodoro => &mut self.pomodoro,
+            Pomodoros::ShortBreak => &mut self.short_break,
+            Pomodoros::LongBreak => &mut self.long_break,
+            Pomodoros::Cooldown => &mut self.cooldown,
+        };
+        *field = duration;
+    }
+
+    pub fn long_break_interval(&self) -> usize {
+        self.long_break_interval
+    }
+
+    /// Every `interval`th pomodoro is followed by a long break; with 1 (or
+    /// 0) every break is a long one.
+    pub fn set_long_break_interval(&mut self, interval: usize) {
+        self.long_break_interval = interval;
+    }
+
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    /// Pomodoros after which the session is finished, 0 to keep going.
+    pub fn set_target(&mut self, target: usize) {
+        self.target = target;
+    }
+
+    pub fn auto_advance(&self) -> bool {
+        self.auto_advance
+    }
+
+    /// Without auto advance the timer pauses at the start of each new
+    /// phase, dropping any time past the end of the last one, until
+    /// `resume` is called.
+    pub fn set_auto_advance(&mut self, auto_advance: bool) {
+        self.auto_advance = auto_advance;
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            phase: self.phase,
+            elapsed: self.elapsed,
+            pomodoros: self.pomodoros,
+            short_breaks: self.short_breaks,
+            long_breaks: self.long_breaks,
+            focus: self.focus,
+        }
+    }
+
+    /// Puts the timer back where `progress` was taken, keeping it paused or
+    /// running and keeping the current phase lengths.
+    pub fn set_progress(&mut self, progress: Progress) {
+        self.phase = progress.phase;
+        self.elapsed = progress.elapsed;
+        self.pomodoros = progress.pomodoros;
+        self.short_breaks = progress.short_breaks;
+        self.long_breaks = progress.long_breaks;
+        self.focus = progress.focus;
+    }
+
+    /// Whether the target has been reached. The timer pauses when it is.
+    pub fn is_finished(&self) -> bool {
+        self.target > 0 && self.pomodoros >= self.target
+    }
+
+    pub fn pomodoros(&self) -> usize {
+        self.pomodoros
+    }
+
+    pub fn short_breaks(&self) -> usize {
+        self.short_breaks
+    }
+
+    pub fn long_breaks(&self) -> usize {
+        self.long_breaks
+    }
+
+    /// Finished pomodoros plus the running one; pauses never count.
+    pub fn focus_time(&self) -> Duration {
+        match self.phase {
+            Pomodoros::Pomodoro => self.focus + self.elapsed,
+            _ => self.focus,
+        }
+    }
+
+    // Counts the current phase, `focused` being the time it counts for,
+    // and moves on to the next one
+    fn complete(&mut self, focused: Duration) {
+        let ended = self.phase;
+        match ended {
+            Pomodoros::Pomodoro => {
+                self.pomodoros += 1;
+                self.focus += focused;
+                if self.is_finished() {
+                    self.running = false;
+                    self.elapsed = Duration::ZERO;
+                    return;
+                }
+            }
+            Pomodoros::ShortBreak => self.short_breaks += 1,
+            Pomodoros::LongBreak => self.long_breaks += 1,
+            Pomodoros::Cooldown => {}
+        }
+
+        self.phase = next_phase(
+            ended,
+            self.pomodoros,
+            self.long_break_interval,
+            !self.cooldown.is_zero(),
+        );
+    }
+}
+
+// The phase after `ended`, where `pomodoros` counts the pomodoros finished
+// so far, `ended` included: every `long_break_interval`th one earns a long
+// break.
+fn next_phase(
+    ended: Pomodoros,
+    pomodoros: usize,
+    long_break_interval: usize,
+    cooldown: bool,
+) -> Pomodoros {
+    match ended {
+        Pomodoros::Pomodoro if pomodoros.is_multiple_of(long_break_interval.max(1)) => {
+            Pomodoros::LongBreak
+        }
+        Pomodoros::Pomodoro => Pomodoros::ShortBreak,
+        Pomodoros::LongBreak if cooldown => Pomodoros::Cooldown,
+        Pomodoros::ShortBreak | Pomodoros::LongBreak | Pomodoros::Cooldown => Pomodoros::Pomodoro,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    fn timer(
+        pomodoro: u64,
+        short_break: u64,
+        long_break: u64,
+        long_break_interval: usize,
+    ) -> PomodoroTimer {
+        let mut timer = PomodoroTimer::new(
+            Duration::from_secs(pomodoro),
+            Duration::from_secs(short_break),
+            Duration::from_secs(long_break),
+            long_break_interval,
+        );
+        timer.resume();
+        timer
+    }
+
+    // One second per tick, which with one second phases finishes a phase on
+    // every tick
+    fn run_phases(timer: &mut PomodoroTimer, phases: usize) -> Vec<Pomodoros> {
+        (0..phases)
+            .map(|_| {
+                timer.tick(SECOND);
+                timer.current_phase()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn next_phase_follows_the_cycle() {
+        let cycle = [
+            (Pomodoros::Pomodoro, 1, Pomodoros::ShortBreak),
+            (Pomodoros::ShortBreak, 1, Pomodoros::Pomodoro),
+            (Pomodoros::Pomodoro, 2, Pomodoros::ShortBreak),
+            (Pomodoros::ShortBreak, 2, Pomodoros::Pomodoro),
+            (Pomodoros::Pomodoro, 3, Pomodoros::LongBreak),
+            (Pomodoros::LongBreak, 3, Pomodoros::Pomodoro),
+        ];
+        for (ended, pomodoros, next) in cycle {
+            assert_eq!(next_phase(ended, pomodoros, 3, false), next);
+        }
+
+        assert_eq!(
+            next_phase(Pomodoros::LongBreak, 3, 3, true),
+            Pomodoros::Cooldown
+        );
+        assert_eq!(
+            next_phase(Pomodoros::Cooldown, 3, 3, true),
+            Pomodoros::Pomodoro
+        );
+    }
+
+    #[test]
+    fn phase_ends_exactly_at_its_duration() {
+        let mut timer = timer(60, 60, 60, 3);
+
+        assert!(timer.tick(59 * SECOND).is_empty());
+        assert_eq!(timer.current_phase(), Pomodoros::Pomodoro);
+        assert_eq!(timer.remaining(), SECOND);
+
+        assert_eq!(timer.tick(SECOND), [Pomodoros::Pomodoro]);
+        assert_eq!(timer.current_phase(), Pomodoros::ShortBreak);
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn fraction_done_follows_the_elapsed_time() {
+        let mut timer = timer(60, 0, 60, 3);
+
+        timer.tick(15 * SECOND);
+        assert_eq!(timer.fraction_done(), 0.25);
+
+        timer.set_phase(Pomodoros::ShortBreak);
+        assert_eq!(timer.fraction_done(), 1.0);
+    }
+
+    #[test]
+    fn a_paused_timer_doesnt_move() {
+        let mut timer = timer(60, 60, 60, 3);
+        timer.tick(10 * SECOND);
+
+        timer.pause();
+        assert!(timer.tick(120 * SECOND).is_empty());
+        assert_eq!(timer.elapsed(), 10 * SECOND);
+
+        timer.resume();
+        timer.tick(55 * SECOND);
+        assert_eq!(timer.current_phase(), Pomodoros::ShortBreak);
+        assert_eq!(timer.elapsed(), 5 * SECOND);
+    }
+
+    #[test]
+    fn zero_length_phases_pass_straight_through() {
+        let mut timer = timer(1, 0, 1, 3);
+
+        let ended = timer.tick(SECOND);
+
+        assert_eq!(ended, [Pomodoros::Pomodoro, Pomodoros::ShortBreak]);
+        assert_eq!(timer.pomodoros(), 1);
+        assert_eq!(timer.short_breaks(), 1);
+        assert_eq!(timer.current_phase(), Pomodoros::Pomodoro);
+    }
+
+    #[test]
+    fn long_break_comes_once_per_cycle() {
+        let mut timer = timer(1, 1, 1, 3);
+
+        let mut long_breaks = Vec::new();
+        for _ in 0..2 {
+            // pomodoro, short, pomodoro, short, pomodoro, long
+            run_phases(&mut timer, 6);
+            long_breaks.push(timer.long_breaks());
+        }
+
+        assert_eq!(long_breaks, [1, 2]);
+        assert_eq!(timer.pomodoros(), 6);
+        assert_eq!(timer.short_breaks(), 4);
+    }
+
+    #[test]
+    fn long_breaks_land_on_every_fourth_pomodoro() {
+        let mut timer = timer(1, 1, 1, 4);
+
+        let mut long_breaks_after = Vec::new();
+        for _ in 0..24 {
+            let ended = timer.tick(SECOND);
+            if timer.current_phase() == Pomodoros::LongBreak && ended == [Pomodoros::Pomodoro] {
+                long_breaks_after.push(timer.pomodoros());
+            }
+        }
+
+        assert_eq!(long_breaks_after, [4, 8, 12]);
+        assert_eq!(timer.short_breaks(), 9);
+    }
+
+    #[test]
+    fn an_interval_of_one_means_every_break_is_long() {
+        let mut timer = timer(1, 1, 1, 1);
+
+        let phases = run_phases(&mut timer, 4);
+
+        assert_eq!(
+            phases,
+            [
+                Pomodoros::LongBreak,
+                Pomodoros::Pomodoro,
+                Pomodoros::LongBreak,
+                Pomodoros::Pomodoro,
+            ]
+        );
+        assert_eq!(timer.long_breaks(), 2);
+    }
+
+    #[test]
+    fn session_stops_at_the_target() {
+        let mut timer = timer(1, 1, 1, 3);
+        timer.set_target(2);
+
+        // pomodoro, short, pomodoro, then nothing more happens
+        run_phases(&mut timer, 6);
+
+        assert!(timer.is_finished());
+        assert!(!timer.is_running());
+        assert_eq!(timer.pomodoros(), 2);
+        assert_eq!(timer.short_breaks(), 1);
+    }
+
+    #[test]
+    fn progress_carries_over_to_a_new_timer() {
+        let mut timer = timer(60, 60, 60, 3);
+        timer.tick(150 * SECOND);
+
+        let mut restored = PomodoroTimer::new(60 * SECOND, 60 * SECOND, 60 * SECOND, 3);
+        restored.set_progress(timer.progress());
+
+        assert_eq!(restored.current_phase(), Pomodoros::Pomodoro);
+        assert_eq!(restored.elapsed(), 30 * SECOND);
+        assert_eq!(restored.pomodoros(), 1);
+        assert_eq!(restored.short_breaks(), 1);
+        assert_eq!(restored.focus_time(), 90 * SECOND);
+    }
+
+    #[test]
+    fn without_auto_advance_the_next_phase_waits() {
+        let mut timer = timer(60, 60, 60, 3);
+        timer.set_auto_advance(false);
+
+        assert_eq!(timer.tick(90 * SECOND), [Pomodoros::Pomodoro]);
+
+        assert!(!timer.is_running());
+        assert_eq!(timer.current_phase(), Pomodoros::ShortBreak);
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn a_skipped_pomodoro_only_counts_the_time_spent() {
+        let mut timer = timer(60, 60, 60, 3);
+        timer.tick(20 * SECOND);
+
+        assert_eq!(timer.skip(), Pomodoros::Pomodoro);
+
+        assert_eq!(timer.pomodoros(), 1);
+        assert_eq!(timer.focus_time(), 20 * SECOND);
+        assert_eq!(timer.current_phase(), Pomodoros::ShortBreak);
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    io::{self, stdout, Write},
+    panic,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use ratatui::crossterm::{
+    cursor::Show,
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event,
+    },
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
+};
+
+/// The terminal state changes the app makes, so setup and teardown can be
+/// checked against a fake terminal.
+pub trait TerminalControl {
+    fn enable_raw_mode(&mut self) -> io::Result<()>;
+    fn disable_raw_mode(&mut self) -> io::Result<()>;
+    fn enter_alternate_screen(&mut self) -> io::Result<()>;
+    fn leave_alternate_screen(&mut self) -> io::Result<()>;
+    fn enable_mouse_capture(&mut self) -> io::Result<()>;
+    fn disable_mouse_capture(&mut self) -> io::Result<()>;
+    fn enable_focus_reporting(&mut self) -> io::Result<()>;
+    fn disable_focus_reporting(&mut self) -> io::Result<()>;
+    /// Puts the window title on the terminal's title stack.
+    fn save_title(&mut self) -> io::Result<()>;
+    fn restore_title(&mut self) -> io::Result<()>;
+    fn show_cursor(&mut self) -> io::Result<()>;
+}
+
+pub struct Crossterm;
+
+impl TerminalControl for Crossterm {
+    fn enable_raw_mode(&mut self) -> io::Result<()> {
+        enable_raw_mode()
+    }
+
+    fn disable_raw_mode(&mut self) -> io::Result<()> {
+        disable_raw_mode()
+    }
+
+    fn enter_alternate_screen(&mut self) -> io::Result<()> {
+        execute!(stdout(), EnterAlternateScreen)
+    }
+
+    fn leave_alternate_screen(&mut self) -> io::Result<()> {
+        execute!(stdout(), LeaveAlternateScreen)
+    }
+
+    fn enable_mouse_capture(&mut self) -> io::Result<()> {
+        execute!(stdout(), EnableMouseCapture)
+    }
+
+    fn disable_mouse_capture(&mut self) -> io::Result<()> {
+        execute!(stdout(), DisableMouseCapture)
+    }
+
+    fn enable_focus_reporting(&mut self) -> io::Result<()> {
+        execute!(stdout(), EnableFocusChange)
+    }
+
+    fn disable_focus_reporting(&mut self) -> io::Result<()> {
+        execute!(stdout(), DisableFocusChange)
+    }
+
+    // xterm's title stack, which tmux and most other terminals also keep;
+    // the rest ignore it
+    fn save_title(&mut self) -> io::Result<()> {
+        let mut stdout = stdout();
+        stdout.write_all(b"\x1b[22;0t")?;
+        stdout.flush()
+    }
+
+    fn restore_title(&mut self) -> io::Result<()> {
+        let mut stdout = stdout();
+        stdout.write_all(b"\x1b[23;0t")?;
+        stdout.flush()
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        execute!(stdout(), Show)
+    }
+}
+
+/// Where the event loop puts the window title, so tests don't retitle the
+/// terminal they run in.
+pub trait WindowTitle {
+    fn set_title(&mut self, title: &str) -> io::Result<()>;
+}
+
+impl WindowTitle for Crossterm {
+    fn set_title(&mut self, title: &str) -> io::Result<()> {
+        execute!(stdout(), SetTitle(title))
+    }
+}
+
+/// Where the event loop's input comes from, so it can be fed a script.
+pub trait EventSource {
+    /// The next event, or `None` if nothing arrived within `timeout`.
+    fn next_event(&mut self, timeout: Duration) -> io::Result<Option<Event>>;
+}
+
+impl EventSource for Crossterm {
+    fn next_event(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+        if event::poll(timeout)? {
+            event::read().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Enables raw mode, enters the alternate screen, captures the mouse, asks
+/// for focus events and saves the window title.
+/// If a later step fails the earlier ones are undone, so an error here
+/// leaves the terminal as it was found.
+pub fn setup(terminal: &mut impl TerminalControl) -> io::Result<()> {
+    terminal.enable_raw_mode()?;
+    let entered = terminal
+        .enter_alternate_screen()
+        .and_then(|()| terminal.enable_mouse_capture())
+        .and_then(|()| terminal.enable_focus_reporting())
+        .and_then(|()| terminal.save_title());
+    entered.inspect_err(|_| {
+        let _ = restore(terminal);
+    })
+}
+
+/// Undoes `setup`. Every step is attempted even if an earlier one fails so
+/// a single error can't leave the terminal half restored; the first error
+/// is the one returned.
+pub fn restore(terminal: &mut impl TerminalControl) -> io::Result<()> {
+    let results = [
+        terminal.restore_title(),
+        terminal.disable_focus_reporting(),
+        terminal.disable_mouse_capture(),
+        terminal.disable_raw_mode(),
+        terminal.leave_alternate_screen(),
+        terminal.show_cursor(),
+    ];
+    results.into_iter().collect()
+}
+
+/// Keeps the terminal set up for as long as it lives. Dropping it restores
+/// the terminal, so an early return can't leave raw mode on; `restore`
+/// does the same but reports what went wrong.
+pub struct Guard<'a, T: TerminalControl> {
+    terminal: &'a mut T,
+    restored: bool,
+}
+
+impl<'a, T: TerminalControl> Guard<'a, T> {
+    pub fn new(terminal: &'a mut T) -> io::Result<Self> {
+        setup(terminal)?;
+        Ok(Guard {
+            terminal,
+            restored: false,
+        })
+    }
+
+    pub fn restore(mut self) -> io::Result<()> {
+        self.restored = true;
+        restore(self.terminal)
+    }
+}
+
+impl<T: TerminalControl> Drop for Guard<'_, T> {
+    fn drop(&mut self) {
+        if !self.restored {
+            let _ = restore(self.terminal);
+        }
+    }
+}
+
+// Set from the signal handler, which can do little else safely
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Turns SIGINT, SIGTERM and SIGHUP into a request to quit, checked with
+/// `interrupted`, so the app gets to restore the terminal on its way out
+/// instead of being killed in raw mode.
+#[cfg(unix)]
+pub fn install_signal_handlers() {
+    extern "C" fn on_signal(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+    }
+    for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+        // SAFETY: the handler only stores to an atomic, which is
+        // async-signal-safe.
+        unsafe {
+            libc::signal(
+                signal,
+                on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            );
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_signal_handlers() {}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Restores the terminal before the default panic message is printed, so a
+/// panic anywhere leaves a usable shell behind.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore(&mut Crossterm);
+        default_hook(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeTerminal {
+        calls: Vec<&'static str>,
+        failing: Option<&'static str>,
+    }
+
+    impl FakeTerminal {
+        fn call(&mut self, name: &'static str) -> io::Result<()> {
+            self.calls.push(name);
+            if self.failing == Some(name) {
+                return Err(io::Error::other(name));
+            }
+            Ok(())
+        }
+    }
+
+    impl TerminalControl for FakeTerminal {
+        fn enable_raw_mode(&mut self) -> io::Result<()> {
+            self.call("enable_raw_mode")
+        }
+
+        fn disable_raw_mode(&mut self) -> io::Result<()> {
+            self.call("disable_raw_mode")
+        }
+
+        fn enter_alternate_screen(&mut self) -> io::Result<()> {
+            self.call("enter_alternate_screen")
+        }
+
+        fn leave_alternate_screen(&mut self) -> io::Result<()> {
+            self.call("leave_alternate_screen")
+        }
+
+        fn enable_mouse_capture(&mut self) -> io::Result<()> {
+            self.call("enable_mouse_capture")
+        }
+
+        fn disable_mouse_capture(&mut self) -> io::Result<()> {
+            self.call("disable_mouse_capture")
+        }
+
+        fn enable_focus_reporting(&mut self) -> io::Result<()> {
+            self.call("enable_focus_reporting")
+        }
+
+        fn disable_focus_reporting(&mut self) -> io::Result<()> {
+            self.call("disable_focus_reporting")
+        }
+
+        fn save_title(&mut self) -> io::Result<()> {
+            self.call("save_title")
+        }
+
+        fn restore_title(&mut self) -> io::Result<()> {
+            self.call("restore_title")
+        }
+
+        fn show_cursor(&mut self) -> io::Result<()> {
+            self.call("show_cursor")
+        }
+    }
+
+    #[test]
+    fn teardown_follows_setup_in_order() {
+        let mut terminal = FakeTerminal::default();
+
+        setup(&mut terminal).unwrap();
+        restore(&mut terminal).unwrap();
+
+        assert_eq!(
+            terminal.calls,
+            [
+                "enable_raw_mode",
+                "enter_alternate_screen",
+                "enable_mouse_capture",
+                "enable_focus_reporting",
+                "save_title",
+                "restore_title",
+                "disable_focus_reporting",
+                "disable_mouse_capture",
+                "disable_raw_mode",
+                "leave_alternate_screen",
+                "show_cursor",
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_setup_leaves_raw_mode_off() {
+        let mut terminal = FakeTerminal {
+            failing: Some("enter_alternate_screen"),
+            ..FakeTerminal::default()
+        };
+
+        let err = setup(&mut terminal).unwrap_err();
+
+        assert_eq!(err.to_string(), "enter_alternate_screen");
+        assert_eq!(
+            terminal.calls,
+            [
+                "enable_raw_mode",
+                "enter_alternate_screen",
+                "restore_title",
+                "disable_focus_reporting",
+                "disable_mouse_capture",
+                "disable_raw_mode",
+                "leave_alternate_screen",
+                "show_cursor",
+            ]
+        );
+    }
+
+    #[test]
+    fn dropping_the_guard_restores_the_terminal() {
+        let mut terminal = FakeTerminal::default();
+
+        drop(Guard::new(&mut terminal).unwrap());
+
+        assert_eq!(
+            terminal.calls[5..],
+            [
+                "restore_title",
+                "disable_focus_reporting",
+                "disable_mouse_capture",
+                "disable_raw_mode",
+                "leave_alternate_screen",
+                "show_cursor",
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_keeps_going_after_a_failed_step() {
+        let mut terminal = FakeTerminal {
+            failing: Some("disable_raw_mode"),
+            ..FakeTerminal::default()
+        };
+
+        let err = restore(&mut terminal).unwrap_err();
+
+        assert_eq!(err.to_string(), "disable_raw_mode");
+        assert_eq!(
+            terminal.calls,
+            [
+                "restore_title",
+                "disable_focus_reporting",
+                "disable_mouse_capture",
+                "disable_raw_mode",
+                "leave_alternate_screen",
+                "show_cursor",
+            ]
+        );
+    }
+}
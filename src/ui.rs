@@ -1,23 +1,392 @@
-use ratatui::prelude::*;
+use ratatui::{prelude::*, widgets::BorderType};
+
+use crate::enums::{anchors::Anchors, pomodoros::Pomodoros, sizes::Sizes};
+
+/// The colors and borders the screens are drawn with, picked by the `theme`
+/// setting and adjusted by the `[colors]` table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub title: Color,
+    pub accent: Color,
+    pub dim: Color,
+    pub success: Color,
+    pub cooldown: Color,
+    pub pomodoro: Color,
+    pub short_break: Color,
+    pub long_break: Color,
+    pub gauge: Color,
+    pub paused: Color,
+    pub warning: Color,
+    pub highlight: Color,
+    pub on_highlight: Color,
+    pub border: BorderType,
+}
+
+impl Theme {
+    /// Changes one entry by its `[colors]` name: a color like `red` or
+    /// `#dc322f`, or for `border` one of `plain`, `rounded`, `double` and
+    /// `thick`.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if name == "border" {
+            self.border = match value {
+                "plain" => BorderType::Plain,
+                "rounded" => BorderType::Rounded,
+                "double" => BorderType::Double,
+                "thick" => BorderType::Thick,
+                _ => return Err(format!("unknown border `{value}`")),
+            };
+            return Ok(());
+        }
+        let color = match name {
+            "title" => &mut self.title,
+            "accent" => &mut self.accent,
+            "dim" => &mut self.dim,
+            "success" => &mut self.success,
+            "cooldown" => &mut self.cooldown,
+            "pomodoro" => &mut self.pomodoro,
+            "short_break" => &mut self.short_break,
+            "long_break" => &mut self.long_break,
+            "gauge" => &mut self.gauge,
+            "paused" => &mut self.paused,
+            "warning" => &mut self.warning,
+            "highlight" => &mut self.highlight,
+            "on_highlight" => &mut self.on_highlight,
+            _ => return Err(format!("unknown color `{name}`")),
+        };
+        *color = value
+            .parse()
+            .map_err(|_| format!("invalid color `{value}` for `{name}`"))?;
+        Ok(())
+    }
+
+    pub fn phase(&self, phase: Pomodoros) -> Color {
+        match phase {
+            Pomodoros::Pomodoro => self.pomodoro,
+            Pomodoros::ShortBreak => self.short_break,
+            Pomodoros::LongBreak => self.long_break,
+            Pomodoros::Cooldown => self.cooldown,
+        }
+    }
+}
 
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    // Cut the given rectangle into three vertical pieces
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
-
-    // Then cut the middle vertical piece into three width-wise pieces
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1] // Return the middle chunk
+    anchored_rect(
+        Sizes::Percent(percent_x),
+        Sizes::Percent(percent_y),
+        Anchors::Center,
+        r,
+    )
+}
+
+pub fn anchored_rect(width: Sizes, height: Sizes, anchor: Anchors, r: Rect) -> Rect {
+    let width = width.resolve(r.width);
+    let height = height.resolve(r.height);
+
+    // Leftover space on each axis, split according to which edge we stick to
+    let free_x = r.width - width;
+    let free_y = r.height - height;
+
+    let x = match anchor {
+        Anchors::TopLeft | Anchors::Left | Anchors::BottomLeft => 0,
+        Anchors::Top | Anchors::Center | Anchors::Bottom => free_x / 2,
+        Anchors::TopRight | Anchors::Right | Anchors::BottomRight => free_x,
+    };
+    let y = match anchor {
+        Anchors::TopLeft | Anchors::Top | Anchors::TopRight => 0,
+        Anchors::Left | Anchors::Center | Anchors::Right => free_y / 2,
+        Anchors::BottomLeft | Anchors::Bottom | Anchors::BottomRight => free_y,
+    };
+
+    Rect::new(r.x + x, r.y + y, width, height)
+}
+
+/// A one-line clickable label, lit up in the accent color while the mouse is
+/// over it.
+pub struct Button<'a> {
+    label: &'a str,
+    theme: Theme,
+    hovered: bool,
+}
+
+impl<'a> Button<'a> {
+    pub fn new(label: &'a str, theme: Theme) -> Self {
+        Button {
+            label,
+            theme,
+            hovered: false,
+        }
+    }
+
+    pub fn hovered(mut self, hovered: bool) -> Self {
+        self.hovered = hovered;
+        self
+    }
+
+    /// Columns the button takes up.
+    pub fn width(&self) -> u16 {
+        self.label.chars().count() as u16
+    }
+}
+
+impl Widget for Button<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let style = if self.hovered {
+            Style::default()
+                .fg(self.theme.on_highlight)
+                .bg(self.theme.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(self.theme.on_highlight)
+                .bg(self.theme.highlight)
+        };
+        buf.set_stringn(area.x, area.y, self.label, area.width as usize, style);
+    }
+}
+
+/// Formats a number of seconds as `2h 5m`, or `45m` / `30s` when shorter.
+pub fn format_duration(seconds: usize) -> String {
+    let (hours, minutes) = (seconds / 3600, seconds % 3600 / 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m"),
+        _ => format!("{hours}h {minutes}m"),
+    }
+}
+
+/// The emoji standing for a phase in one-line views.
+pub fn phase_icon(phase: Pomodoros) -> &'static str {
+    match phase {
+        Pomodoros::Pomodoro => "🍅",
+        Pomodoros::ShortBreak | Pomodoros::LongBreak => "☕",
+        Pomodoros::Cooldown => "🌱",
+    }
+}
+
+/// Formats a number of seconds as `MM:SS`.
+pub fn format_clock(seconds: usize) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Rows in each glyph drawn by `big_text`.
+pub const BIG_TEXT_HEIGHT: u16 = 5;
+
+// Five rows per glyph, digits then the colon
+const GLYPHS: [[&str; 5]; 11] = [
+    ["█████", "█   █", "█   █", "█   █", "█████"],
+    ["    █", "    █", "    █", "    █", "    █"],
+    ["█████", "    █", "█████", "█    ", "█████"],
+    ["█████", "    █", "█████", "    █", "█████"],
+    ["█   █", "█   █", "█████", "    █", "    █"],
+    ["█████", "█    ", "█████", "    █", "█████"],
+    ["█████", "█    ", "█████", "█   █", "█████"],
+    ["█████", "    █", "    █", "    █", "    █"],
+    ["█████", "█   █", "█████", "█   █", "█████"],
+    ["█████", "█   █", "█████", "    █", "█████"],
+    [" ", "█", " ", "█", " "],
+];
+
+/// Renders digits and colons as large block letters, one string per row,
+/// or `None` if `text` has anything else in it.
+pub fn big_text(text: &str) -> Option<Vec<String>> {
+    let glyphs = text
+        .chars()
+        .map(|c| match c {
+            ':' => Some(&GLYPHS[10]),
+            c => c.to_digit(10).map(|digit| &GLYPHS[digit as usize]),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let rows = (0..BIG_TEXT_HEIGHT as usize)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    Some(rows)
+}
+
+/// Blows up rows from `big_text` by `scale` in both directions, for clocks
+/// that have a whole terminal to fill.
+pub fn scale_text(rows: &[String], scale: usize) -> Vec<String> {
+    rows.iter()
+        .flat_map(|row| {
+            let wide: String = row
+                .chars()
+                .flat_map(|c| std::iter::repeat_n(c, scale))
+                .collect();
+            std::iter::repeat_n(wide, scale)
+        })
+        .collect()
+}
+
+/// Formats minutes since midnight as `14:05`, or `2:05 PM` on a 12-hour clock.
+pub fn format_time_of_day(minute_of_day: u32, twelve_hour: bool) -> String {
+    let (hour, minute) = (minute_of_day / 60 % 24, minute_of_day % 60);
+    if !twelve_hour {
+        return format!("{hour:02}:{minute:02}");
+    }
+    let suffix = if hour < 12 { "AM" } else { "PM" };
+    let hour = match hour % 12 {
+        0 => 12,
+        hour => hour,
+    };
+    format!("{hour}:{minute:02} {suffix}")
+}
+
+/// Drops every color for `NO_COLOR`. Cells drawn on a background, like the
+/// selected row, are reversed instead so they still stand out.
+pub fn strip_colors(buffer: &mut Buffer) {
+    for cell in &mut buffer.content {
+        if cell.bg != Color::Reset {
+            cell.modifier.insert(Modifier::REVERSED);
+        }
+        cell.fg = Color::Reset;
+        cell.bg = Color::Reset;
+    }
+}
+
+/// Swaps the borders, bars and symbols for ASCII look-alikes, for terminals
+/// that can't show more. Text typed in by the user is left alone.
+pub fn to_ascii(buffer: &mut Buffer) {
+    for cell in &mut buffer.content {
+        if let Some(ascii) = ascii_symbol(cell.symbol()) {
+            cell.set_symbol(ascii);
+        }
+    }
+}
+
+fn ascii_symbol(symbol: &str) -> Option<&'static str> {
+    let mut chars = symbol.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    let ascii = match c {
+        '─' | '━' | '═' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' => "-",
+        '│' | '┃' | '║' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' => "|",
+        // The other box drawing characters are all corners and joints
+        '\u{2500}'..='\u{257f}' => "+",
+        // Full and partial blocks, from the gauges, the bars and the big clock
+        '\u{2580}'..='\u{259f}' => "#",
+        '•' | '★' => "*",
+        '☆' => "o",
+        '·' | '—' => "-",
+        '◀' | '←' => "<",
+        '▶' | '→' => ">",
+        '↑' => "^",
+        '↓' => "v",
+        '⏸' => "=",
+        '✔' => "v",
+        '🍅' => "P",
+        '☕' => "B",
+        '🌱' => "C",
+        '🔥' => "!",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::themes::Themes;
+
+    #[test]
+    fn theme_entries_are_set_by_name() {
+        let mut theme = Themes::Dark.palette();
+        assert_eq!(theme.set("accent", "#dc322f"), Ok(()));
+        assert_eq!(theme.set("dim", "lightblue"), Ok(()));
+        assert_eq!(theme.set("border", "double"), Ok(()));
+        assert_eq!(theme.accent, Color::Rgb(0xdc, 0x32, 0x2f));
+        assert_eq!(theme.dim, Color::LightBlue);
+        assert_eq!(theme.border, BorderType::Double);
+
+        assert!(theme.set("accent", "#12").is_err());
+        assert!(theme.set("border", "dotted").is_err());
+        assert!(theme.set("background", "red").is_err());
+        assert_eq!(theme.accent, Color::Rgb(0xdc, 0x32, 0x2f));
+    }
+
+    #[test]
+    fn centered_rect_keeps_its_old_shape() {
+        let area = Rect::new(0, 0, 100, 40);
+        assert_eq!(centered_rect(60, 25, area), Rect::new(20, 15, 60, 10));
+    }
+
+    #[test]
+    fn corners_touch_the_edges() {
+        let area = Rect::new(2, 3, 80, 24);
+        let size = (Sizes::Cells(20), Sizes::Cells(5));
+
+        assert_eq!(
+            anchored_rect(size.0, size.1, Anchors::TopLeft, area),
+            Rect::new(2, 3, 20, 5)
+        );
+        assert_eq!(
+            anchored_rect(size.0, size.1, Anchors::BottomRight, area),
+            Rect::new(62, 22, 20, 5)
+        );
+    }
+
+    #[test]
+    fn edges_center_on_the_other_axis() {
+        let area = Rect::new(0, 0, 80, 24);
+
+        assert_eq!(
+            anchored_rect(Sizes::Percent(50), Sizes::Cells(4), Anchors::Bottom, area),
+            Rect::new(20, 20, 40, 4)
+        );
+        assert_eq!(
+            anchored_rect(Sizes::Cells(10), Sizes::Percent(50), Anchors::Left, area),
+            Rect::new(0, 6, 10, 12)
+        );
+    }
+
+    #[test]
+    fn oversized_requests_are_clamped_to_small_areas() {
+        let area = Rect::new(5, 5, 8, 3);
+
+        assert_eq!(
+            anchored_rect(
+                Sizes::Cells(40),
+                Sizes::Cells(10),
+                Anchors::BottomRight,
+                area
+            ),
+            area
+        );
+        assert_eq!(
+            anchored_rect(Sizes::Percent(150), Sizes::Percent(50), Anchors::Top, area),
+            Rect::new(5, 5, 8, 1)
+        );
+    }
+
+    #[test]
+    fn time_of_day_in_both_clocks() {
+        assert_eq!(format_time_of_day(14 * 60 + 5, false), "14:05");
+        assert_eq!(format_time_of_day(14 * 60 + 5, true), "2:05 PM");
+        assert_eq!(format_time_of_day(5, true), "12:05 AM");
+        assert_eq!(format_time_of_day(12 * 60, true), "12:00 PM");
+    }
+
+    #[test]
+    fn big_text_lines_up_its_rows() {
+        let rows = big_text("12:05").unwrap();
+
+        assert_eq!(rows.len(), BIG_TEXT_HEIGHT as usize);
+        assert!(rows.iter().all(|row| row.chars().count() == 25));
+        assert_eq!(rows[0], "    █ █████   █████ █████");
+        assert_eq!(big_text("1h"), None);
+    }
+
+    #[test]
+    fn scaled_text_repeats_rows_and_columns() {
+        let rows = scale_text(&big_text("1").unwrap(), 2);
+
+        assert_eq!(rows.len(), 2 * BIG_TEXT_HEIGHT as usize);
+        assert_eq!(rows[0], "        ██");
+        assert_eq!(rows[1], rows[0]);
+    }
 }